@@ -0,0 +1,134 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    helpers::input_path,
+    runner::{run_day, RunOutput},
+    DAYS,
+};
+
+const CACHE_DIR: &str = "./target/aoc-cache";
+
+// Everything that can change a solver's answers. A run answers every part at once, so one entry
+// covers all of a day's parts
+pub struct CacheKey {
+    pub day_number: usize,
+    pub implementation: &'static str,
+    pub params: Vec<(&'static str, String)>,
+    pub input: Vec<u8>,
+}
+
+impl CacheKey {
+    fn describe(&self) -> String {
+        let params =
+            self.params.iter().map(|(name, value)| format!("{name}={value}")).collect::<Vec<_>>();
+        format!(
+            "day={} impl={} params=[{}] input={:016x}",
+            self.day_number,
+            self.implementation,
+            params.join(","),
+            fnv1a_hash(&self.input)
+        )
+    }
+}
+
+// Entries live under a directory named after the running binary, so a rebuild never sees answers
+// from older code. Debug and release builds are different binaries that get rebuilt in turn, so
+// each profile keeps its own directory and only clears out its own stale builds
+pub struct Cache {
+    build_dir: PathBuf,
+}
+
+impl Cache {
+    pub fn open() -> Cache {
+        let build_id = std::env::current_exe()
+            .and_then(fs::read)
+            .map(|exe| fnv1a_hash(&exe))
+            .expect("Could not read the running binary");
+        let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+        let profile_dir = Path::new(CACHE_DIR).join(profile);
+        let build_dir = profile_dir.join(format!("{build_id:016x}"));
+
+        // Entries from any other build of this profile are stale
+        if !build_dir.exists() {
+            let _ = fs::remove_dir_all(profile_dir);
+        }
+
+        Cache { build_dir }
+    }
+
+    // The file holds the key it was stored under (guarding against hash collisions), the original
+    // solve time in nanoseconds, and then the solver's output
+    pub fn lookup(&self, key: &CacheKey) -> Option<RunOutput> {
+        let contents = fs::read_to_string(self.file_path(key)).ok()?;
+        let mut lines = contents.lines();
+        if lines.next()? != key.describe() {
+            return None;
+        }
+        let elapsed = Duration::from_nanos(lines.next()?.parse().ok()?);
+
+        Some(RunOutput::from_printed(lines, String::new(), elapsed, true))
+    }
+
+    // Failures to write are not fatal, the answer just won't be cached
+    pub fn store(&self, key: &CacheKey, output: &RunOutput) {
+        let mut contents = format!("{}\n{}\n", key.describe(), output.elapsed.as_nanos());
+        for line in output.printed_lines() {
+            contents.push_str(&line);
+            contents.push('\n');
+        }
+        let _ = fs::create_dir_all(&self.build_dir)
+            .and_then(|_| fs::write(self.file_path(key), contents));
+    }
+
+    fn file_path(&self, key: &CacheKey) -> PathBuf {
+        self.build_dir.join(format!("{:016x}.txt", fnv1a_hash(key.describe().as_bytes())))
+    }
+}
+
+// Solves a day with its default implementation and parameters, going through the cache if there is
+// one. Also returns whether the answers came from the cache
+pub fn run_day_cached(
+    cache: Option<&Cache>,
+    day_number: usize,
+) -> std::io::Result<(RunOutput, bool)> {
+    // Without an input there's nothing to key the cache on, but the solver still gets to report the
+    // problem itself
+    let day = &DAYS[day_number - 1];
+    let key = fs::read(input_path(day_number)).ok().map(|input| CacheKey {
+        day_number,
+        implementation: day.implementations[0].name,
+        params: day.params.iter().map(|param| (param.name, param.default.to_string())).collect(),
+        input,
+    });
+
+    if let (Some(cache), Some(key)) = (cache, &key) {
+        if let Some(output) = cache.lookup(key) {
+            return Ok((output, true));
+        }
+    }
+
+    let output = run_day(day_number, &[])?;
+    if let (true, Some(cache), Some(key)) = (output.success, cache, &key) {
+        cache.store(key, &output);
+    }
+    Ok((output, false))
+}
+
+pub fn clear() -> std::io::Result<()> {
+    match fs::remove_dir_all(CACHE_DIR) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+// The standard library's hashers are not guaranteed to be stable between builds, so roll our own
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &b| (hash ^ b as u64).wrapping_mul(PRIME))
+}
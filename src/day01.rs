@@ -1,23 +1,186 @@
-use crate::helpers::iterate_file_lines;
+use std::io::BufRead;
+
+use crate::{
+    fatal_error,
+    helpers::{iterate_file_lines, print_answer, stream_input, Param},
+};
+
+pub const NOTES: &str = "Streams through the input a line at a time, keeping a running top three \
+    with a sorted insert.";
 
 pub fn solve() {
-    let mut largest_three = [0usize; 4]; // One extra to avoid bounds checks in copy_within
-    let mut cur_total: usize = 0;
-
-    for line in iterate_file_lines("day01input.txt") {
-        if line.is_empty() {
-            let insert_index = largest_three[0..3].partition_point(|&x| x > cur_total);
-            if insert_index < 3 {
-                largest_three.copy_within(insert_index..2, insert_index + 1);
-                largest_three[insert_index] = cur_total;
+    let largest_three = match sum_largest::<3>(stream_input("day01input.txt")) {
+        Ok(largest) => largest,
+        Err(e) => fatal_error(&e.to_string()),
+    };
+
+    print_answer(1, format_args!("The elf carrying the most calories is {}", largest_three[0]));
+    print_answer(
+        2,
+        format_args!("The three highest-calorie elves are {}", largest_three.iter().sum::<usize>()),
+    );
+}
+
+#[derive(Debug)]
+enum InputError {
+    Read(std::io::Error),
+    Malformed { line_number: usize, line: String },
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Read(e) => write!(f, "Could not read input: {e}"),
+            InputError::Malformed { line_number, line } => {
+                write!(f, "Line {line_number}: expected a calorie count, found {line:?}")
+            }
+        }
+    }
+}
+
+// An empty line separates elves, anything else should be a calorie count
+fn parse_line(line_number: usize, line: &str) -> Result<Option<usize>, InputError> {
+    if line.is_empty() {
+        return Ok(None);
+    }
+    line.parse()
+        .map(Some)
+        .map_err(|_| InputError::Malformed { line_number, line: line.to_string() })
+}
+
+// Streams through the elves, keeping only the `N` largest totals seen so far, largest first. Only
+// one line is held at a time, so the input can be as long as it likes
+fn sum_largest<const N: usize>(mut reader: impl BufRead) -> Result<[usize; N], InputError> {
+    let mut largest = [0usize; N];
+    let mut cur_total: Option<usize> = None;
+
+    let mut commit = |total: usize| {
+        let insert_index = largest.partition_point(|&x| x > total);
+        if insert_index < N {
+            largest.copy_within(insert_index..N - 1, insert_index + 1);
+            largest[insert_index] = total;
+        }
+    };
+
+    let mut line = String::new();
+    for line_number in 1.. {
+        line.clear();
+        if reader.read_line(&mut line).map_err(InputError::Read)? == 0 {
+            break;
+        }
+
+        match parse_line(line_number, line.trim_end_matches(['\n', '\r']))? {
+            Some(calories) => *cur_total.get_or_insert(0) += calories,
+            None => cur_total.take().into_iter().for_each(&mut commit),
+        }
+    }
+
+    // The input doesn't have to end with a blank line, so the last elf may still be pending
+    cur_total.into_iter().for_each(commit);
+
+    Ok(largest)
+}
+
+pub const TOP_K: Param = Param {
+    name: "k",
+    default: "3",
+    description: "How many of the highest-calorie elves the ranked table shows",
+};
+
+pub const HISTOGRAM_BUCKETS: Param = Param {
+    name: "buckets",
+    default: "10",
+    description: "Number of buckets in the histogram of elf totals",
+};
+
+// Ranks the elves and summarizes how the calories are spread between them
+pub fn solve_ranked() {
+    let k: usize = TOP_K.get();
+    let elves = parse_elves().unwrap_or_else(|e| fatal_error(&e.to_string()));
+    if elves.is_empty() {
+        fatal_error("No elves in the input");
+    }
+
+    let top = top_k(&elves, k);
+    println!("Top {} elves:", top.len());
+    println!("{:>4}  {:>5}  {:>5}  {:>8}", "Rank", "Elf", "Items", "Calories");
+    for (rank, elf) in top.iter().enumerate() {
+        println!("{:>4}  {:>5}  {:>5}  {:>8}", rank + 1, elf.index, elf.items.len(), elf.total);
+    }
+    println!("Top {} total: {}", top.len(), top.iter().map(|elf| elf.total).sum::<usize>());
+
+    let mut totals: Vec<usize> = elves.iter().map(|elf| elf.total).collect();
+    totals.sort_unstable();
+
+    let mean = totals.iter().sum::<usize>() as f64 / totals.len() as f64;
+    let mid = totals.len() / 2;
+    let median = if totals.len() % 2 == 1 {
+        totals[mid] as f64
+    } else {
+        (totals[mid - 1] + totals[mid]) as f64 / 2.0
+    };
+    println!("{} elves, mean {mean:.1}, median {median:.1}", totals.len());
+
+    print_histogram(&totals, HISTOGRAM_BUCKETS.get());
+}
+
+struct Elf {
+    // Counting from 1, in input order
+    index: usize,
+    items: Vec<usize>,
+    total: usize,
+}
+
+fn parse_elves() -> Result<Vec<Elf>, InputError> {
+    let mut elves = Vec::new();
+    let mut items = Vec::new();
+
+    // Chaining on an empty line means the last elf doesn't need a trailing blank line to count
+    let lines = iterate_file_lines("day01input.txt").chain([String::new()]);
+    for (i, line) in lines.enumerate() {
+        match parse_line(i + 1, &line)? {
+            Some(calories) => items.push(calories),
+            None if !items.is_empty() => {
+                let total = items.iter().sum();
+                elves.push(Elf {
+                    index: elves.len() + 1,
+                    items: std::mem::take(&mut items),
+                    total,
+                });
             }
-            cur_total = 0;
-        } else {
-            let calories: usize = line.parse().expect("Expected parseable number");
-            cur_total += calories;
+            None => {}
         }
     }
 
-    println!("The elf carrying the most calories is {}", largest_three[0]);
-    println!("The three highest-calorie elves are {}", largest_three[0..3].iter().sum::<usize>());
+    Ok(elves)
+}
+
+// Highest total first. Ties go to whichever elf came first
+fn top_k(elves: &[Elf], k: usize) -> Vec<&Elf> {
+    let mut ranked: Vec<&Elf> = elves.iter().collect();
+    ranked.sort_by(|a, b| b.total.cmp(&a.total).then(a.index.cmp(&b.index)));
+    ranked.truncate(k);
+    ranked
+}
+
+// Expects the totals to be sorted
+fn print_histogram(totals: &[usize], num_buckets: usize) {
+    const MAX_BAR_WIDTH: usize = 40;
+    assert!(num_buckets > 0, "Need at least one histogram bucket");
+
+    let (min, max) = (totals[0], totals[totals.len() - 1]);
+    let bucket_width = (max - min) / num_buckets + 1;
+
+    let mut counts = vec![0usize; num_buckets];
+    for &total in totals {
+        counts[(total - min) / bucket_width] += 1;
+    }
+
+    let largest_count = *counts.iter().max().unwrap();
+    println!("Histogram of totals:");
+    for (i, &count) in counts.iter().enumerate() {
+        let low = min + i * bucket_width;
+        let bar = "#".repeat((count * MAX_BAR_WIDTH).div_ceil(largest_count));
+        println!("{:>7}-{:<7} {count:>4} {bar}", low, low + bucket_width - 1);
+    }
 }
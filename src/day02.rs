@@ -1,109 +1,432 @@
-use crate::helpers::iterate_file_lines;
+use crate::{
+    fatal_error,
+    helpers::{iterate_file_lines, print_answer, Param},
+};
+
+pub const NOTES: &str = "The game's rules are data: choices sit in a cycle where each beats the \
+    ones before it, so a round's result comes from the distance between the two choices.";
+
+pub const CHOICES: Param = Param {
+    name: "choices",
+    default: "Rock:1,Paper:2,Scissors:3",
+    description: "NAME:SCORE for each choice, in order around the cycle. Each choice beats the \
+        (N-1)/2 choices before it, e.g. Rock:1,Spock:2,Paper:3,Lizard:4,Scissors:5",
+};
+
+pub const OUTCOME_SCORES: Param = Param {
+    name: "outcome_scores",
+    default: "0,3,6",
+    description: "Points for losing, drawing and winning a round",
+};
 
 pub fn solve() {
-    solve_part1();
-    solve_part2();
+    let game = Game::from_params().unwrap_or_else(|e| fatal_error(&e));
+    solve_part1(&game);
+    solve_part2(&game);
 }
 
-#[derive(PartialEq, Eq)]
-enum Choice {
-    Rock,
-    Paper,
-    Scissors,
+// The elf never said what the second column means, so try every way of reading it: as our choice
+// (any assignment of letters to choices) or as the outcome (any assignment of X, Y and Z to
+// outcomes). Each reading's total is reported, along with the best, worst and expected (the mean,
+// if every reading is equally likely) for both kinds of reading
+pub fn solve_decode() {
+    const OUTCOMES: [Outcome; 3] = [Outcome::Lost, Outcome::Draw, Outcome::Won];
+    // 9! mappings is still quick to score, but 11! isn't
+    const MAX_CHOICES: usize = 9;
+
+    let game = Game::from_params().unwrap_or_else(|e| fatal_error(&e));
+    let n = game.num_choices();
+    if n > MAX_CHOICES {
+        fatal_error(&format!(
+            "Too many choices to try every mapping, decoding handles at most {MAX_CHOICES}"
+        ));
+    }
+
+    // Only how often each pair of letters comes up matters, so tally those once up front
+    let mut pair_counts = vec![vec![0usize; n]; n];
+    for line in iterate_file_lines("day02input.txt") {
+        let (opp, mine) = parse_line(&line);
+        pair_counts[game.to_opponent_choice(opp)][game.to_my_choice(mine)] += 1;
+    }
+    let letters: Vec<char> = (0..n).map(|idx| game.my_letter(idx)).collect();
+
+    // Mapping letter index -> choice index
+    let choice_readings: Vec<(String, usize)> = permutations(n)
+        .into_iter()
+        .map(|mapping| {
+            let total = sum_pairs(&pair_counts, |opp, letter| {
+                let my_choice = mapping[letter];
+                calc_points(&game, my_choice, game.outcome(my_choice, opp))
+            });
+            let description = (0..n)
+                .map(|letter| format!("{}={}", letters[letter], game.choices[mapping[letter]].name))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (description, total)
+        })
+        .collect();
+    print_readings("our choice", &choice_readings);
+
+    // Outcomes only come in three letters, anything else in the column rules this reading out
+    let outcome_letters = ['X', 'Y', 'Z'];
+    let all_outcome_letters = (0..n).all(|letter| {
+        outcome_letters.contains(&letters[letter]) || pair_counts.iter().all(|row| row[letter] == 0)
+    });
+    let outcome_readings: Vec<(String, usize)> = if all_outcome_letters {
+        permutations(3)
+            .into_iter()
+            .map(|mapping| {
+                let total = sum_pairs(&pair_counts, |opp, letter| {
+                    let outcome_idx = outcome_letters.iter().position(|&c| c == letters[letter]);
+                    let outcome = OUTCOMES[mapping[outcome_idx.expect("Checked above")]];
+                    calc_points(&game, game.choice_for_outcome(opp, outcome), outcome)
+                });
+                let description = (0..3)
+                    .map(|i| format!("{}={:?}", outcome_letters[i], OUTCOMES[mapping[i]]))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                (description, total)
+            })
+            .collect()
+    } else {
+        println!("The second column can't be outcomes, it uses letters other than X, Y and Z");
+        Vec::new()
+    };
+    print_readings("the outcome", &outcome_readings);
+
+    let best = choice_readings
+        .iter()
+        .map(|(description, total)| ("our choice", description, total))
+        .chain(
+            outcome_readings.iter().map(|(description, total)| ("the outcome", description, total)),
+        )
+        .max_by_key(|(_, _, &total)| total)
+        .expect("There's always at least one reading");
+    println!("Highest total is {} reading the second column as {}: {}", best.2, best.0, best.1);
 }
 
-enum Result {
-    Won,
-    Lost,
-    Draw,
+fn print_readings(kind: &str, readings: &[(String, usize)]) {
+    if readings.is_empty() {
+        return;
+    }
+
+    println!("Reading the second column as {kind}:");
+    let mut sorted: Vec<_> = readings.iter().collect();
+    sorted.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+    for (description, total) in sorted {
+        println!("  {description}: {total}");
+    }
+
+    let totals = readings.iter().map(|(_, total)| *total);
+    let expected = totals.clone().sum::<usize>() as f64 / readings.len() as f64;
+    println!(
+        "  Best {}, worst {}, expected {expected:.1}",
+        totals.clone().max().unwrap(),
+        totals.min().unwrap()
+    );
 }
 
-fn to_opponent_choice(c: char) -> Choice {
-    match c {
-        'A' => Choice::Rock,
-        'B' => Choice::Paper,
-        _ => Choice::Scissors,
+// Totals up the points for every round, given the points for one round of each pair of letters
+fn sum_pairs(pair_counts: &[Vec<usize>], points: impl Fn(usize, usize) -> usize) -> usize {
+    let mut total = 0;
+    for (opp, row) in pair_counts.iter().enumerate() {
+        for (letter, &count) in row.iter().enumerate().filter(|(_, &count)| count > 0) {
+            total += count * points(opp, letter);
+        }
     }
+    total
 }
 
-fn calc_points(my_choice: Choice, result: Result) -> usize {
-    let me_points = match my_choice {
-        Choice::Rock => 1,
-        Choice::Paper => 2,
-        Choice::Scissors => 3,
-    };
-    let result_points = match result {
-        Result::Won => 6,
-        Result::Lost => 0,
-        Result::Draw => 3,
-    };
-    me_points + result_points
+// Every ordering of 0..n, starting with the identity
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    fn recurse(cur: &mut Vec<usize>, used: &mut [bool], out: &mut Vec<Vec<usize>>) {
+        if cur.len() == used.len() {
+            out.push(cur.clone());
+            return;
+        }
+        for i in 0..used.len() {
+            if !used[i] {
+                used[i] = true;
+                cur.push(i);
+                recurse(cur, used, out);
+                cur.pop();
+                used[i] = false;
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    recurse(&mut Vec::with_capacity(n), &mut vec![false; n], &mut out);
+    out
 }
 
-fn solve_part1() {
-    let mut total = 0usize;
+// Looks for patterns in the opponent's column. Two models are fitted to the whole tournament: how
+// often each choice comes up, and how often each choice follows each other one. Each gives a
+// counter-strategy, scored as if we'd known the model from the start. Then an adaptive player, who
+// only knows the rounds so far, predicts each round from the transitions seen up to then
+pub fn solve_opponent() {
+    let game = Game::from_params().unwrap_or_else(|e| fatal_error(&e));
+    let n = game.num_choices();
 
+    let mut opponent = Vec::new();
+    let mut guide_total = 0usize;
     for line in iterate_file_lines("day02input.txt") {
-        fn to_my_choice(c: char) -> Choice {
-            match c {
-                'X' => Choice::Rock,
-                'Y' => Choice::Paper,
-                _ => Choice::Scissors,
-            }
-        }
+        let (opp, mine) = parse_line(&line);
+        let opp = game.to_opponent_choice(opp);
+        let my_choice = game.to_my_choice(mine);
+        guide_total += calc_points(&game, my_choice, game.outcome(my_choice, opp));
+        opponent.push(opp);
+    }
+    assert!(!opponent.is_empty(), "No rounds in the input");
+
+    let mut frequencies = vec![0usize; n];
+    let mut transitions = vec![vec![0usize; n]; n];
+    for &opp in &opponent {
+        frequencies[opp] += 1;
+    }
+    for pair in opponent.windows(2) {
+        transitions[pair[0]][pair[1]] += 1;
+    }
+
+    let name = |choice: usize| game.choices[choice].name.as_str();
+    let width = game.choices.iter().map(|choice| choice.name.len()).max().unwrap().max(5);
 
-        let mut chars = line.chars();
-        let opp = to_opponent_choice(chars.next().expect("Malformed line"));
-        chars.next();
-        let my_choice = to_my_choice(chars.next().expect("Malformed line"));
-
-        let result = match (&my_choice, opp) {
-            (Choice::Rock, Choice::Rock) => Result::Draw,
-            (Choice::Rock, Choice::Paper) => Result::Lost,
-            (Choice::Rock, Choice::Scissors) => Result::Won,
-            (Choice::Paper, Choice::Rock) => Result::Won,
-            (Choice::Paper, Choice::Paper) => Result::Draw,
-            (Choice::Paper, Choice::Scissors) => Result::Lost,
-            (Choice::Scissors, Choice::Rock) => Result::Lost,
-            (Choice::Scissors, Choice::Paper) => Result::Won,
-            (Choice::Scissors, Choice::Scissors) => Result::Draw,
+    println!("Opponent's choices over {} rounds:", opponent.len());
+    for (choice, &count) in frequencies.iter().enumerate() {
+        let percent = 100.0 * count as f64 / opponent.len() as f64;
+        println!("  {:<width$} {count:>6} {percent:>5.1}%", name(choice));
+    }
+
+    println!("Transitions (previous choice down the side, next along the top):");
+    print!("  {:<width$}", "");
+    (0..n).for_each(|choice| print!(" {:>width$}", name(choice)));
+    println!();
+    for (prev, row) in transitions.iter().enumerate() {
+        print!("  {:<width$}", name(prev));
+        row.iter().for_each(|count| print!(" {count:>width$}"));
+        println!();
+    }
+
+    println!("Guide total: {guide_total}");
+
+    let counter = best_response(&game, &frequencies);
+    let total: usize =
+        opponent.iter().map(|&opp| calc_points(&game, counter, game.outcome(counter, opp))).sum();
+    println!("Always playing {} (best against the frequencies): {total}", name(counter));
+
+    // The first round has nothing before it, so it falls back on the frequencies
+    let answers: Vec<usize> = transitions.iter().map(|row| best_response(&game, row)).collect();
+    let total: usize = opponent
+        .iter()
+        .enumerate()
+        .map(|(i, &opp)| {
+            let my_choice = if i == 0 { counter } else { answers[opponent[i - 1]] };
+            calc_points(&game, my_choice, game.outcome(my_choice, opp))
+        })
+        .sum();
+    let answers = (0..n)
+        .map(|prev| format!("{}->{}", name(prev), name(answers[prev])))
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!("Answering the previous choice (best against the transitions, {answers}): {total}");
+
+    println!("Adaptive player predicting from history: {}", simulate_adaptive(&game, &opponent));
+}
+
+// Plays the tournament knowing only the rounds already played. Each round is predicted from what
+// followed the opponent's last choice before, or from the overall counts if that choice is new.
+// Counts start at one each, so early on every choice is treated as possible
+fn simulate_adaptive(game: &Game, opponent: &[usize]) -> usize {
+    let n = game.num_choices();
+    let mut frequencies = vec![1usize; n];
+    let mut transitions = vec![vec![1usize; n]; n];
+    let mut prev: Option<usize> = None;
+    let mut total = 0;
+
+    for &opp in opponent {
+        let prediction = match prev {
+            Some(prev) if transitions[prev].iter().sum::<usize>() > n => &transitions[prev],
+            _ => &frequencies,
         };
+        let my_choice = best_response(game, prediction);
+        total += calc_points(game, my_choice, game.outcome(my_choice, opp));
 
-        total += calc_points(my_choice, result);
+        frequencies[opp] += 1;
+        if let Some(prev) = prev {
+            transitions[prev][opp] += 1;
+        }
+        prev = Some(opp);
     }
 
-    println!("Part 1 total points: {total}");
+    total
 }
 
-fn solve_part2() {
+// The choice with the highest expected score against an opponent who picks each choice with the
+// given weights. Ties go to the earlier choice
+fn best_response(game: &Game, weights: &[usize]) -> usize {
+    let expected = |my_choice: usize| -> usize {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(opp, &weight)| {
+                weight * calc_points(game, my_choice, game.outcome(my_choice, opp))
+            })
+            .sum()
+    };
+    (0..game.num_choices()).rev().max_by_key(|&choice| expected(choice)).unwrap()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Outcome {
+    Lost,
+    Draw,
+    Won,
+}
+
+struct Choice {
+    name: String,
+    score: usize,
+}
+
+// Choices are referred to by their index in the cycle. The opponent's column names them with
+// letters starting from A, and ours with letters ending at Z (so X, Y and Z for three choices)
+struct Game {
+    choices: Vec<Choice>,
+    outcome_scores: [usize; 3],
+}
+
+impl Game {
+    // The rules come from the command line, so a game that can't be played is reported up front
+    // rather than left to panic partway through
+    fn from_params() -> Result<Self, String> {
+        let choices = CHOICES
+            .get::<String>()
+            .split(',')
+            .map(|choice| {
+                let (name, score) = choice
+                    .split_once(':')
+                    .ok_or_else(|| format!("Expected choices as NAME:SCORE, found {choice:?}"))?;
+                let score =
+                    score.parse().map_err(|_| format!("Malformed score {score:?} for {name}"))?;
+                Ok(Choice { name: name.to_string(), score })
+            })
+            .collect::<Result<Vec<Choice>, String>>()?;
+
+        // A lone choice can only draw, and with an even number each choice has one opposite it
+        // that it neither beats nor loses to
+        let n = choices.len();
+        if !(3..=13).contains(&n) || n % 2 != 1 {
+            return Err(format!(
+                "Need an odd number of choices from 3 to 13 for every pair to have a winner, \
+                    found {n}"
+            ));
+        }
+        if let Some(i) = (1..n).find(|&i| choices[..i].iter().any(|c| c.name == choices[i].name)) {
+            return Err(format!("The choice {} is listed twice", choices[i].name));
+        }
+
+        let outcome_scores = OUTCOME_SCORES
+            .get::<String>()
+            .split(',')
+            .map(|score| score.parse().map_err(|_| format!("Malformed outcome score {score:?}")))
+            .collect::<Result<Vec<usize>, String>>()?
+            .try_into()
+            .map_err(|scores: Vec<usize>| {
+                format!("Expected three outcome scores, found {}", scores.len())
+            })?;
+
+        Ok(Self { choices, outcome_scores })
+    }
+
+    fn num_choices(&self) -> usize {
+        self.choices.len()
+    }
+
+    fn outcome(&self, my_choice: usize, opp: usize) -> Outcome {
+        let n = self.num_choices();
+        match (my_choice + n - opp) % n {
+            0 => Outcome::Draw,
+            distance if distance <= n / 2 => Outcome::Won,
+            _ => Outcome::Lost,
+        }
+    }
+
+    // With more than three choices, several can give the same outcome. Pick whichever scores best
+    fn choice_for_outcome(&self, opp: usize, outcome: Outcome) -> usize {
+        (0..self.num_choices())
+            .filter(|&choice| self.outcome(choice, opp) == outcome)
+            .max_by_key(|&choice| self.choices[choice].score)
+            .expect("With an odd number of choices, every outcome is possible against any choice")
+    }
+
+    fn to_opponent_choice(&self, c: char) -> usize {
+        let idx = (c as usize).wrapping_sub('A' as usize);
+        assert!(idx < self.num_choices(), "Unknown opponent choice {c}");
+        idx
+    }
+
+    fn to_my_choice(&self, c: char) -> usize {
+        let idx = (c as usize).wrapping_sub(self.my_letter(0) as usize);
+        assert!(idx < self.num_choices(), "Unknown choice {c}");
+        idx
+    }
+
+    fn my_letter(&self, choice: usize) -> char {
+        (b'Z' + 1 - self.num_choices() as u8 + choice as u8) as char
+    }
+}
+
+fn calc_points(game: &Game, my_choice: usize, outcome: Outcome) -> usize {
+    game.choices[my_choice].score + game.outcome_scores[outcome as usize]
+}
+
+// Splits a line into its two column letters
+fn parse_line(line: &str) -> (char, char) {
+    let mut chars = line.chars();
+    let opp = chars.next().expect("Malformed line");
+    chars.next();
+    (opp, chars.next().expect("Malformed line"))
+}
+
+fn solve_part1(game: &Game) {
     let mut total = 0usize;
 
     for line in iterate_file_lines("day02input.txt") {
-        fn to_my_result(c: char) -> Result {
-            match c {
-                'X' => Result::Lost,
-                'Y' => Result::Draw,
-                _ => Result::Won,
-            }
+        let (opp, mine) = parse_line(&line);
+        let opp = game.to_opponent_choice(opp);
+        let my_choice = game.to_my_choice(mine);
+
+        total += calc_points(game, my_choice, game.outcome(my_choice, opp));
+    }
+
+    print_answer(1, format_args!("Part 1 total points: {total}"));
+}
+
+fn solve_part2(game: &Game) {
+    fn to_my_outcome(c: char) -> Option<Outcome> {
+        match c {
+            'X' => Some(Outcome::Lost),
+            'Y' => Some(Outcome::Draw),
+            'Z' => Some(Outcome::Won),
+            _ => None,
         }
+    }
+
+    let mut total = 0usize;
+
+    for (i, line) in iterate_file_lines("day02input.txt").enumerate() {
+        let (opp, outcome) = parse_line(&line);
+        let opp = game.to_opponent_choice(opp);
 
-        let mut chars = line.chars();
-        let opp = to_opponent_choice(chars.next().expect("Malformed line"));
-        chars.next();
-        let result = to_my_result(chars.next().expect("Malformed line"));
-
-        let my_choice = match (&result, &opp) {
-            (Result::Won, Choice::Rock) => Choice::Paper,
-            (Result::Won, Choice::Paper) => Choice::Scissors,
-            (Result::Won, Choice::Scissors) => Choice::Rock,
-            (Result::Draw, _) => opp,
-            (Result::Lost, Choice::Rock) => Choice::Scissors,
-            (Result::Lost, Choice::Paper) => Choice::Rock,
-            (Result::Lost, Choice::Scissors) => Choice::Paper,
+        // With more than three choices, a guide written for part 1 can use letters before X
+        let Some(outcome) = to_my_outcome(outcome) else {
+            println!("Part 2 needs X, Y or Z in the second column, line {} has {outcome}", i + 1);
+            return;
         };
 
-        total += calc_points(my_choice, result);
+        let my_choice = game.choice_for_outcome(opp, outcome);
+        total += calc_points(game, my_choice, outcome);
     }
 
-    println!("Part 2 total points: {total}");
+    print_answer(2, format_args!("Part 2 total points: {total}"));
 }
@@ -1,51 +1,258 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer, Param};
+
+pub const NOTES: &str = "Each rucksack is a bitmask with one bit per item priority, so the items \
+    shared between compartments, or across a group of rucksacks, are a single AND.";
+
+pub const GROUP_SIZE: Param = Param {
+    name: "group_size",
+    default: "3",
+    description: "How many elves share a badge in part 2",
+};
 
 pub fn solve() {
     solve_part1();
     solve_part2();
 }
 
+// One bit per priority, so bits 1 to 52 are in use
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Items(u64);
+
+impl Items {
+    const ALL: Items = Items(u64::MAX);
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Items(bytes.iter().fold(0, |mask, &c| mask | 1 << get_priority(c)))
+    }
+
+    fn intersect(self, other: Items) -> Items {
+        Items(self.0 & other.0)
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    fn priorities(self) -> impl Iterator<Item = usize> {
+        (1..=52).filter(move |&priority| self.0 & 1 << priority != 0)
+    }
+
+    fn total_priority(self) -> usize {
+        self.priorities().sum()
+    }
+}
+
+impl std::fmt::Display for Items {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items: String = self.priorities().map(get_item).collect();
+        write!(f, "{items}")
+    }
+}
+
 fn get_priority(c: u8) -> usize {
     (match c {
-        c if c >= 97 => c - 96, // Lowercase
-        c => c - 38,            // Uppercase
+        b'a'..=b'z' => c - 96,
+        b'A'..=b'Z' => c - 38,
+        _ => panic!("Unexpected item {:?}", c as char),
     }) as usize
 }
 
+fn get_item(priority: usize) -> char {
+    (match priority {
+        1..=26 => priority + 96,
+        _ => priority + 38,
+    }) as u8 as char
+}
+
+// There should be exactly one shared item. If not, every shared item still counts towards the
+// total, but the line gets a warning so the input can be checked
+fn warn_unless_single(what: &str, common: Items) {
+    match common.len() {
+        1 => {}
+        0 => eprintln!("Warning: {what} share no items"),
+        n => eprintln!("Warning: {what} share {n} items: {common}"),
+    }
+}
+
+fn read_rucksacks() -> Vec<Items> {
+    iterate_file_lines("day03input.txt").map(|line| Items::from_bytes(line.as_bytes())).collect()
+}
+
 fn solve_part1() {
     let mut total = 0usize;
 
-    for line in iterate_file_lines("day03input.txt") {
-        // We know it's ASCII
-        let half_point = line.len() / 2;
-        let (first_comp, second_comp) =
-            (line[..half_point].as_bytes(), line[half_point..].as_bytes());
+    for (i, line) in iterate_file_lines("day03input.txt").enumerate() {
+        if line.len() % 2 == 1 {
+            eprintln!("Warning: line {} has an odd number of items, so no middle", i + 1);
+        }
 
-        // We know there's exactly one match
-        let common =
-            *first_comp.iter().find(|b| second_comp.contains(b)).expect("Should have been a match");
+        let (first_comp, second_comp) = line.as_bytes().split_at(line.len() / 2);
+        let common = Items::from_bytes(first_comp).intersect(Items::from_bytes(second_comp));
+        warn_unless_single(&format!("line {}'s compartments", i + 1), common);
 
-        total += get_priority(common);
+        total += common.total_priority();
     }
 
-    println!("Part 1 total: {total}");
+    print_answer(1, format_args!("Part 1 total: {total}"));
 }
 
 fn solve_part2() {
+    let group_size: usize = GROUP_SIZE.get();
+    assert!(group_size > 0, "Groups need at least one elf");
+
+    let rucksacks = read_rucksacks();
+    let leftover = rucksacks.len() % group_size;
+    if leftover > 0 {
+        eprintln!("Warning: the last {leftover} elves don't make a whole group of {group_size}");
+    }
+
     let mut total = 0usize;
 
-    for [l1, l2, l3] in iterate_file_lines("day03input.txt").array_chunks::<3>() {
-        // We know it's ASCII
-        let (b1, b2, b3) = (l1.as_bytes(), l2.as_bytes(), l3.as_bytes());
+    for (i, group) in rucksacks.chunks_exact(group_size).enumerate() {
+        let common = group.iter().fold(Items::ALL, |common, &items| common.intersect(items));
+        let first_line = i * group_size + 1;
+        let what = format!("lines {first_line} to {}", first_line + group_size - 1);
+        warn_unless_single(&what, common);
 
-        // We know there's exactly one match
-        let common = *b1
-            .iter()
-            .find(|b| b2.contains(b) && b3.contains(b))
-            .expect("Should have been a match");
+        total += common.total_priority();
+    }
 
-        total += get_priority(common);
+    print_answer(2, format_args!("Part 2 total: {total}"));
+}
+
+// For when the lines have been shuffled and the groups are lost. Every group that shares exactly
+// one item is a candidate, and the search picks candidates until every elf is in one. It always
+// branches on the elf with the fewest candidates left, and backs out as soon as any elf has none
+pub fn solve_shuffled() {
+    let group_size: usize = GROUP_SIZE.get();
+    assert!(group_size > 0, "Groups need at least one elf");
+
+    let rucksacks = read_rucksacks();
+    let leftover = rucksacks.len() % group_size;
+    if leftover > 0 {
+        println!("{} elves can't be split into groups of {group_size}", rucksacks.len());
+        return;
+    }
+
+    let mut candidates = Vec::new();
+    find_candidates(&rucksacks, group_size, &mut Vec::new(), Items::ALL, &mut candidates);
+
+    let mut search = GroupSearch {
+        elf_candidates: vec![Vec::new(); rucksacks.len()],
+        candidates,
+        assigned: vec![false; rucksacks.len()],
+        chosen: Vec::new(),
+        solutions: Vec::new(),
+    };
+    for (i, candidate) in search.candidates.iter().enumerate() {
+        candidate.iter().for_each(|&elf| search.elf_candidates[elf].push(i));
+    }
+    println!("{} candidate groups share exactly one item", search.candidates.len());
+
+    search.search();
+
+    let Some(solution) = search.solutions.first() else {
+        println!("No way to split the elves into groups sharing exactly one item");
+        return;
+    };
+    if search.solutions.len() > 1 {
+        println!("Found a split, but it isn't unique");
+    } else {
+        println!("Found a split, and it's the only one");
+    }
+
+    let mut groups: Vec<&Vec<usize>> =
+        solution.iter().map(|&candidate| &search.candidates[candidate]).collect();
+    groups.sort();
+    let mut total = 0usize;
+    for group in groups {
+        let badge = group.iter().fold(Items::ALL, |common, &elf| common.intersect(rucksacks[elf]));
+        let lines = group.iter().map(|elf| (elf + 1).to_string()).collect::<Vec<_>>().join(", ");
+        println!("  Lines {lines}: badge {badge}");
+        total += badge.total_priority();
+    }
+    println!("Badge total: {total}");
+}
+
+// Collects every group (as elf indices in increasing order) that shares exactly one item. Groups
+// are built an elf at a time, and abandoned once their members have nothing left in common
+fn find_candidates(
+    rucksacks: &[Items],
+    group_size: usize,
+    group: &mut Vec<usize>,
+    common: Items,
+    out: &mut Vec<Vec<usize>>,
+) {
+    if group.len() == group_size {
+        if common.len() == 1 {
+            out.push(group.clone());
+        }
+        return;
+    }
+
+    let first = group.last().map_or(0, |&last| last + 1);
+    for elf in first..rucksacks.len() {
+        let common = common.intersect(rucksacks[elf]);
+        if common.len() == 0 {
+            continue;
+        }
+        group.push(elf);
+        find_candidates(rucksacks, group_size, group, common, out);
+        group.pop();
+    }
+}
+
+struct GroupSearch {
+    candidates: Vec<Vec<usize>>,
+    // For each elf, the candidates it's in
+    elf_candidates: Vec<Vec<usize>>,
+    assigned: Vec<bool>,
+    chosen: Vec<usize>,
+    // Finding a second one is enough to know the answer isn't unique
+    solutions: Vec<Vec<usize>>,
+}
+
+impl GroupSearch {
+    fn is_open(&self, candidate: usize) -> bool {
+        self.candidates[candidate].iter().all(|&elf| !self.assigned[elf])
     }
 
-    println!("Part 2 total: {total}");
+    fn search(&mut self) {
+        if self.solutions.len() >= 2 {
+            return;
+        }
+
+        // The elf with the fewest candidates whose members are all still unassigned
+        let mut most_constrained: Option<(usize, usize)> = None;
+        for elf in (0..self.assigned.len()).filter(|&elf| !self.assigned[elf]) {
+            let open = self.elf_candidates[elf].iter().filter(|&&c| self.is_open(c)).count();
+            if open == 0 {
+                return;
+            }
+            if !matches!(most_constrained, Some((_, fewest)) if fewest <= open) {
+                most_constrained = Some((elf, open));
+            }
+        }
+
+        let Some((elf, _)) = most_constrained else {
+            self.solutions.push(self.chosen.clone());
+            return;
+        };
+
+        let open: Vec<usize> =
+            self.elf_candidates[elf].iter().copied().filter(|&c| self.is_open(c)).collect();
+        for candidate in open {
+            self.set_assigned(candidate, true);
+            self.chosen.push(candidate);
+            self.search();
+            self.chosen.pop();
+            self.set_assigned(candidate, false);
+        }
+    }
+
+    fn set_assigned(&mut self, candidate: usize, assigned: bool) {
+        for &elf in &self.candidates[candidate] {
+            self.assigned[elf] = assigned;
+        }
+    }
 }
@@ -1,16 +1,17 @@
-use crate::helpers::iterate_file_lines;
+use crate::{
+    fatal_error,
+    helpers::{iterate_file_lines, print_answer},
+    intervals::{IntervalSet, Range},
+};
 use std::cmp::Ordering;
 
+pub const NOTES: &str = "Compares each pair's ranges for containment and overlap.";
+
 pub fn solve() {
     solve_part1();
     solve_part2();
 }
 
-struct Range {
-    start: usize,
-    end: usize,
-}
-
 fn parse_range(s: &str) -> Range {
     let (a, b) = s.split_once('-').expect("Malformed pair");
     Range { start: a.parse().expect("Malformed A"), end: b.parse().expect("Malformed B") }
@@ -40,7 +41,7 @@ fn solve_part1() {
         }
     }
 
-    println!("Total is {num_contained}");
+    print_answer(1, format_args!("Total is {num_contained}"));
 }
 
 fn solve_part2() {
@@ -55,5 +56,280 @@ fn solve_part2() {
         }
     }
 
-    println!("Total is {num_contained}");
+    print_answer(2, format_args!("Total is {num_contained}"));
+}
+
+// Looks at the assignments all together rather than in pairs: how many elves cover each section,
+// which sections nobody covers, and which assignments could be dropped without losing anything
+pub fn solve_coverage() {
+    let assignments = parse_assignments();
+    if assignments.is_empty() {
+        fatal_error("No assignments in the input");
+    }
+
+    let runs = coverage_runs(assignments.iter().map(|(_, range)| *range));
+    let span = Range { start: runs[0].0.start, end: runs.last().unwrap().0.end };
+    println!("{} assignments covering sections {span}", assignments.len());
+
+    println!("Elves covering each section:");
+    for (range, count) in &runs {
+        println!("  {:>9}: {count}", range.to_string());
+    }
+
+    let covered = IntervalSet::from_ranges(assignments.iter().map(|(_, range)| *range));
+    let uncovered = IntervalSet::from_ranges([span]).difference(&covered);
+    if uncovered.is_empty() {
+        println!("Every section is covered");
+    } else {
+        println!("Sections nobody covers: {uncovered}");
+    }
+
+    let most = runs.iter().map(|(_, count)| *count).max().unwrap();
+    let busiest = IntervalSet::from_ranges(
+        runs.iter().filter(|(_, count)| *count == most).map(|(range, _)| *range),
+    );
+    println!("Most over-assigned, with {most} elves: {busiest}");
+
+    // An assignment is covered by everyone else exactly when each of its sections has at least
+    // one other elf on it
+    let shared = IntervalSet::from_ranges(
+        runs.iter().filter(|(_, count)| *count >= 2).map(|(range, _)| *range),
+    );
+    let redundant: Vec<_> =
+        assignments.iter().filter(|(_, range)| shared.contains_range(*range)).collect();
+    println!("{} assignments are redundant given all the others:", redundant.len());
+    for (elf, range) in redundant {
+        println!("  {elf}: {range}");
+    }
+}
+
+// Which elf an assignment belongs to, as a line and which of the pair it is
+struct Elf {
+    line_number: usize,
+    second: bool,
+}
+
+impl std::fmt::Display for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let which = if self.second { "second" } else { "first" };
+        write!(f, "Line {} {which} elf", self.line_number)
+    }
+}
+
+fn parse_assignments() -> Vec<(Elf, Range)> {
+    let mut assignments = Vec::new();
+    for (i, line) in iterate_file_lines("day04input.txt").enumerate() {
+        let (a_str, b_str) = line.split_once(',').expect("Malformed line");
+        assignments.push((Elf { line_number: i + 1, second: false }, parse_range(a_str)));
+        assignments.push((Elf { line_number: i + 1, second: true }, parse_range(b_str)));
+    }
+    assignments
+}
+
+// Splits the sections from the first one covered up to the last into runs with the same number of elves
+// on them, found by sweeping over where assignments start and stop
+fn coverage_runs(ranges: impl Iterator<Item = Range>) -> Vec<(Range, usize)> {
+    let mut changes: Vec<(usize, isize)> = Vec::new();
+    for range in ranges {
+        changes.push((range.start, 1));
+        changes.push((range.end + 1, -1));
+    }
+    changes.sort_unstable();
+
+    let mut runs: Vec<(Range, usize)> = Vec::new();
+    let (mut count, mut start) = (0isize, changes.first().map_or(0, |&(section, _)| section));
+    for (section, change) in changes {
+        if section > start {
+            // Where one assignment ends just as another starts, the count doesn't change
+            match runs.last_mut() {
+                Some((last, last_count)) if *last_count == count as usize => last.end = section - 1,
+                _ => runs.push((Range { start, end: section - 1 }, count as usize)),
+            }
+            start = section;
+        }
+        count += change;
+    }
+    runs
+}
+
+// Changes as few assignments as possible so that no two elves share a section, while the same
+// sections stay covered. The assignments kept as they are must be disjoint, and every stretch of
+// covered sections between them goes to one of the changed elves. So the aim is to keep as many as
+// possible while leaving no more stretches than there are changed elves to fill them. Any changed
+// elves left over get no sections at all
+pub fn solve_reassign() {
+    let assignments = parse_assignments();
+    let ranges: Vec<Range> = assignments.iter().map(|(_, range)| *range).collect();
+    let covered = IntervalSet::from_ranges(ranges.iter().copied());
+
+    let kept = choose_kept(&ranges, &covered);
+    let stretches = covered.difference(&IntervalSet::from_ranges(kept.iter().map(|&i| ranges[i])));
+
+    let mut stretches_left = stretches.ranges().iter();
+    let new_ranges: Vec<Option<Range>> = (0..ranges.len())
+        .map(|i| if kept.contains(&i) { Some(ranges[i]) } else { stretches_left.next().copied() })
+        .collect();
+
+    let num_changed = ranges.len() - kept.len();
+    println!("Keeping {} assignments and changing {num_changed}", kept.len());
+    println!(
+        "Of the changed elves, {} take a stretch of sections each and {} get none",
+        stretches.ranges().len(),
+        num_changed - stretches.ranges().len()
+    );
+    for (i, (elf, range)) in assignments.iter().enumerate().filter(|(i, _)| !kept.contains(i)) {
+        match new_ranges[i] {
+            Some(new_range) => println!("  {elf}: {range} -> {new_range}"),
+            None => println!("  {elf}: {range} -> none"),
+        }
+    }
+
+    // Lines where both elves have sections are in the same format as the input, so they can be fed
+    // back in. An elf with no sections has nothing to write there, so those lines come after
+    let as_text = |range: &Range| format!("{}-{}", range.start, range.end);
+    let mut written: Vec<String> = Vec::new();
+    let mut partial_lines = Vec::new();
+    println!("New assignments:");
+    for (line, pair) in new_ranges.chunks(2).enumerate() {
+        if let [Some(a), Some(b)] = pair {
+            println!("{},{}", as_text(a), as_text(b));
+            written.extend([as_text(a), as_text(b)]);
+        } else {
+            partial_lines.push(line);
+        }
+    }
+    if !partial_lines.is_empty() {
+        println!("Lines with an elf left without sections:");
+    }
+    for line in partial_lines {
+        for i in [2 * line, 2 * line + 1] {
+            let text = new_ranges[i].as_ref().map(as_text);
+            println!("  {}: {}", assignments[i].0, text.as_deref().unwrap_or("none"));
+            written.extend(text);
+        }
+    }
+
+    // Checks what was written rather than what was worked out, so it goes back through the parser
+    // and the same tests as parts 1 and 2, for every pair of elves
+    let assigned: Vec<Range> = written.iter().map(|text| parse_range(text)).collect();
+    for (i, &a) in assigned.iter().enumerate() {
+        for &b in &assigned[i + 1..] {
+            assert!(
+                !has_overlap(a, b) && !has_containment(a, b),
+                "New assignments {a} and {b} overlap"
+            );
+        }
+    }
+    assert!(IntervalSet::from_ranges(assigned) == covered, "New assignments change the coverage");
+    println!("Checked: no two elves overlap, and the same sections are covered");
+}
+
+// Returns the indices of the assignments to keep. The sections are split into segments at every
+// point where an assignment starts or stops, and a dynamic program walks through them tracking how
+// many assignments have been kept and whether it's partway through a stretch left for a changed
+// elf, minimising the number of stretches for each number kept
+fn choose_kept(ranges: &[Range], covered: &IntervalSet) -> Vec<usize> {
+    struct Step {
+        num_stretches: usize,
+        prev_state: usize,
+        kept: Option<usize>,
+    }
+
+    let mut bounds: Vec<usize> =
+        ranges.iter().flat_map(|range| [range.start, range.end + 1]).collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+    let num_segments = bounds.len().saturating_sub(1);
+    let segment_at = |section: usize| bounds.binary_search(&section).unwrap();
+
+    let mut starting_at = vec![Vec::new(); num_segments];
+    for (i, range) in ranges.iter().enumerate() {
+        starting_at[segment_at(range.start)].push(i);
+    }
+
+    // Never more than fit side by side, which taking the earliest finishing first finds
+    let mut by_end: Vec<&Range> = ranges.iter().collect();
+    by_end.sort_unstable_by_key(|range| range.end);
+    let mut max_kept = 0;
+    let mut next_free = 0;
+    for range in by_end {
+        if range.start >= next_free {
+            max_kept += 1;
+            next_free = range.end + 1;
+        }
+    }
+
+    let state = |segment: usize, num_kept: usize, in_stretch: bool| {
+        (segment * (max_kept + 1) + num_kept) * 2 + in_stretch as usize
+    };
+    let mut best: Vec<Option<Step>> =
+        (0..(num_segments + 1) * (max_kept + 1) * 2).map(|_| None).collect();
+    best[state(0, 0, false)] = Some(Step { num_stretches: 0, prev_state: usize::MAX, kept: None });
+
+    let relax = |best: &mut [Option<Step>], to: usize, step: Step| {
+        if !matches!(&best[to], Some(cur) if cur.num_stretches <= step.num_stretches) {
+            best[to] = Some(step);
+        }
+    };
+
+    for segment in 0..num_segments {
+        let segment_covered = covered.contains(bounds[segment]);
+        for num_kept in 0..=max_kept {
+            for in_stretch in [false, true] {
+                let from = state(segment, num_kept, in_stretch);
+                let Some(num_stretches) = best[from].as_ref().map(|step| step.num_stretches) else {
+                    continue;
+                };
+
+                if !segment_covered {
+                    let step = Step { num_stretches, prev_state: from, kept: None };
+                    relax(&mut best, state(segment + 1, num_kept, false), step);
+                    continue;
+                }
+
+                // Left for a changed elf, which starts a new stretch unless one is underway
+                let step = Step {
+                    num_stretches: num_stretches + !in_stretch as usize,
+                    prev_state: from,
+                    kept: None,
+                };
+                relax(&mut best, state(segment + 1, num_kept, true), step);
+
+                if num_kept < max_kept {
+                    for &i in &starting_at[segment] {
+                        let to = state(segment_at(ranges[i].end + 1), num_kept + 1, false);
+                        relax(
+                            &mut best,
+                            to,
+                            Step { num_stretches, prev_state: from, kept: Some(i) },
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    // Keeping none always works, as long as there's an elf for every stretch of covered sections
+    let mut end_state = None;
+    for num_kept in (0..=max_kept).rev() {
+        end_state = [false, true]
+            .into_iter()
+            .map(|in_stretch| state(num_segments, num_kept, in_stretch))
+            .filter(|&s| {
+                best[s].as_ref().is_some_and(|step| step.num_stretches <= ranges.len() - num_kept)
+            })
+            .min_by_key(|&s| best[s].as_ref().unwrap().num_stretches);
+        if end_state.is_some() {
+            break;
+        }
+    }
+
+    let mut kept = Vec::new();
+    let mut cur = end_state.expect("Need at least as many elves as stretches of covered sections");
+    while let Some(step) = best.get(cur).and_then(Option::as_ref) {
+        kept.extend(step.kept);
+        cur = step.prev_state;
+    }
+    kept.sort_unstable();
+    kept
 }
@@ -1,21 +1,314 @@
-use std::fs::read_to_string;
+use std::io::{IsTerminal, Write};
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer, read_input_file, Param};
+
+pub const NOTES: &str = "Parses the crate drawing into stacks, then replays each move by draining \
+    off the top of one stack onto another. Moving crates one at a time is the same as reversing \
+    the drained crates.";
 
 pub fn solve() {
     solve_part1();
     solve_part2();
 }
 
-pub fn solve_short() {
-    println!("Moved one at a time: {}", solve_short_impl(false));
-    println!("Moved multiple at a time: {}", solve_short_impl(true));
+pub const CAPACITY: Param = Param {
+    name: "capacity",
+    default: "1",
+    description: "Most crates the crane can lift in one grab, or \"all\" for no limit",
+};
+
+// Runs the moves with a crane of the chosen capacity. Part 1's crane has a capacity of one and
+// part 2's has no limit
+pub fn solve_crane() {
+    let crane = CrateMover::from_params();
+
+    let mut lines = iterate_file_lines("day05input.txt");
+    let mut crate_stacks = parse_crate_stacks(&mut lines);
+
+    lines.next(); // Skip a line
+    let mut grabs = 0;
+    for line in lines {
+        grabs += crane.apply(&mut crate_stacks, &parse_move_line(&line));
+    }
+
+    println!("The message is {}", collect_message(&crate_stacks));
+    println!("The crane made {grabs} grabs");
+}
+
+pub const FPS: Param = Param {
+    name: "fps",
+    default: "10",
+    description: "Frames per second when playing the moves in the terminal",
+};
+
+pub const HIGHLIGHT: Param = Param {
+    name: "highlight",
+    default: "true",
+    description: "Whether to pick out the crates each move has just moved",
+};
+
+pub const FRAMES_FILE: Param = Param {
+    name: "frames_file",
+    default: "",
+    description: "Write every frame to this file instead of playing them in the terminal",
+};
+
+// Draws the stacks in the puzzle's own layout after every move, using the crane from the
+// `capacity` parameter. In a terminal it plays them back in place. Otherwise, or when writing to a
+// file, the frames just follow each other
+pub fn show_moves() {
+    let crane = CrateMover::from_params();
+    let highlight: bool = HIGHLIGHT.get();
+    let frames_file: String = FRAMES_FILE.get();
+    let fps: f64 = FPS.get();
+    assert!(fps > 0.0, "Need a positive number of frames per second");
+
+    let to_file = !frames_file.is_empty();
+    let animate = !to_file && std::io::stdout().is_terminal();
+    // Colours would only be noise outside a terminal, so it marks moved crates with braces instead
+    let style = if animate { Highlight::Inverse } else { Highlight::Braces };
+
+    let mut lines = iterate_file_lines("day05input.txt");
+    let mut crate_stacks = parse_crate_stacks(&mut lines);
+    lines.next(); // Skip a line
+    let moves: Vec<Move> = lines.map(|line| parse_move_line(&line)).collect();
+
+    let mut frames = vec![format!("Start\n{}", render_stacks(&crate_stacks, None))];
+    for (i, mv) in moves.iter().enumerate() {
+        crane.apply(&mut crate_stacks, mv);
+        let moved = highlight.then_some((mv.dest_stack, mv.number, style));
+        frames.push(format!(
+            "Move {} of {}: move {} from {} to {}\n{}",
+            i + 1,
+            moves.len(),
+            mv.number,
+            mv.src_stack + 1,
+            mv.dest_stack + 1,
+            render_stacks(&crate_stacks, moved)
+        ));
+    }
+
+    if to_file {
+        std::fs::write(&frames_file, frames.join("\n"))
+            .unwrap_or_else(|e| panic!("Could not write {frames_file}: {e}"));
+        println!("Wrote {} frames to {frames_file}", frames.len());
+    } else if animate {
+        let delay = std::time::Duration::from_secs_f64(1.0 / fps);
+        for frame in &frames {
+            // Clear the screen and go back to the top left
+            print!("\x1b[2J\x1b[H{frame}");
+            std::io::stdout().flush().expect("Could not write frame");
+            std::thread::sleep(delay);
+        }
+    } else {
+        frames.iter().for_each(|frame| println!("{frame}"));
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Highlight {
+    // Swapped foreground and background, for terminals
+    Inverse,
+    // {X} rather than [X]
+    Braces,
+}
+
+// Draws the stacks the way the puzzle input does, with the columns as wide as the widest label.
+// `moved` picks out the top crates of one stack
+fn render_stacks(crate_stacks: &StackSet, moved: Option<(usize, usize, Highlight)>) -> String {
+    let label_width = crate_stacks.iter().flatten().map(|label| label.len()).max().unwrap_or(1);
+    let column_width = label_width + 2;
+    let height = crate_stacks.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut out = String::new();
+    for level in (0..height).rev() {
+        let mut row = String::new();
+        for (i, stack) in crate_stacks.iter().enumerate() {
+            if i > 0 {
+                row.push(' ');
+            }
+            let Some(label) = stack.get(level) else {
+                row.push_str(&" ".repeat(column_width));
+                continue;
+            };
+
+            let is_moved =
+                moved.filter(|&(idx, number, _)| idx == i && level + number >= stack.len());
+            let label = format!("{label:^label_width$}");
+            match is_moved.map(|(_, _, style)| style) {
+                None => row.push_str(&format!("[{label}]")),
+                Some(Highlight::Inverse) => row.push_str(&format!("\x1b[7m[{label}]\x1b[0m")),
+                Some(Highlight::Braces) => row.push_str(&format!("{{{label}}}")),
+            }
+        }
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+
+    let numbers: Vec<String> =
+        (1..=crate_stacks.len()).map(|n| format!("{n:^column_width$}")).collect();
+    out.push_str(numbers.join(" ").trim_end());
+    out.push('\n');
+    out
+}
+
+pub const FINAL_LAYOUT: Param = Param {
+    name: "final_layout",
+    default: "",
+    description: "File with a drawing of the stacks after the moves, for the reverse \
+        implementation. By default it's whatever the moves make of the input's layout",
+};
+
+pub const MESSAGE: Param = Param {
+    name: "message",
+    default: "",
+    description: "Top-of-stack message for the reverse implementation to work back from, as the \
+        label on each stack separated by commas, with nothing between them for a stack that ends \
+        up empty. The input's layout then only gives the stack heights",
+};
+
+// Runs the moves backwards, for both the crane that moves one crate at a time and the one that
+// moves them all at once. From a full final arrangement there's exactly one starting layout. From
+// only a message, the moves decide which starting crates end up on top, and the rest could be
+// anything
+pub fn solve_reverse() {
+    let final_layout: String = FINAL_LAYOUT.get();
+    let message: String = MESSAGE.get();
+
+    let mut lines = iterate_file_lines("day05input.txt");
+    let input_stacks = parse_crate_stacks(&mut lines);
+    lines.next(); // Skip a line
+    let moves: Vec<Move> = lines.map(|line| parse_move_line(&line)).collect();
+
+    let cranes = [
+        ("One at a time", CrateMover { capacity: Some(1) }),
+        ("All at once", CrateMover { capacity: None }),
+    ];
+    for (name, crane) in cranes {
+        println!("{name}:");
+        if message.is_empty() {
+            reverse_from_layout(&crane, &input_stacks, &moves, &final_layout);
+        } else {
+            reverse_from_message(&crane, &input_stacks, &moves, &message);
+        }
+    }
+}
+
+fn reverse_from_layout(crane: &CrateMover, input_stacks: &StackSet, moves: &[Move], path: &str) {
+    let final_stacks = if path.is_empty() {
+        let mut crate_stacks = input_stacks.clone();
+        for mv in moves {
+            crane.apply(&mut crate_stacks, mv);
+        }
+        crate_stacks
+    } else {
+        match std::fs::read_to_string(path) {
+            Ok(drawing) => parse_crate_stacks(&mut drawing.lines()),
+            Err(e) => return println!("  Could not read {path}: {e}"),
+        }
+    };
+
+    // A layout that didn't come from these moves may not have the stacks or crates they need
+    let stacks_used = moves.iter().map(|mv| mv.src_stack.max(mv.dest_stack) + 1).max().unwrap_or(0);
+    if final_stacks.len() < stacks_used {
+        return println!(
+            "  No layout fits, the moves use {stacks_used} stacks but the final layout has {}",
+            final_stacks.len()
+        );
+    }
+    let mut crate_stacks = final_stacks.clone();
+    for (i, mv) in moves.iter().enumerate().rev() {
+        if let Err(e) = crane.undo(&mut crate_stacks, mv) {
+            return println!("  No layout fits, move {} can't be undone: {e}", i + 1);
+        }
+    }
+    println!("Starting layout:");
+    print!("{}", render_stacks(&crate_stacks, None));
+
+    // Going forwards again has to land back on the same arrangement
+    let mut replayed = crate_stacks.clone();
+    for mv in moves {
+        crane.apply(&mut replayed, mv);
+    }
+    assert!(replayed == final_stacks, "Replaying the moves didn't give the final arrangement");
+    if path.is_empty() {
+        assert!(&crate_stacks == input_stacks, "Undoing the moves didn't give the input's layout");
+        println!("Undoing the moves gives back the input's layout");
+    } else {
+        println!("Replaying the moves from it gives the final arrangement");
+        if &crate_stacks == input_stacks {
+            println!("It's the same as the input's layout");
+        }
+    }
+}
+
+// Every crate is given a number standing for its starting place, and the moves are played on those
+// numbers to see which starting places end up on top
+fn reverse_from_message(
+    crane: &CrateMover,
+    input_stacks: &StackSet,
+    moves: &[Move],
+    message: &str,
+) {
+    let mut next_place = 0;
+    let mut places: StackSet = input_stacks
+        .iter()
+        .map(|stack| {
+            next_place += stack.len();
+            (next_place - stack.len()..next_place).map(|place| place.to_string()).collect()
+        })
+        .collect();
+    let starting_places = places.clone();
+    for mv in moves {
+        crane.apply(&mut places, mv);
+    }
+
+    let labels: Vec<&str> = message.split(',').map(str::trim).collect();
+    if labels.len() != places.len() {
+        println!(
+            "  The message has {} labels, but needs one for each of the {} stacks",
+            labels.len(),
+            places.len()
+        );
+        return;
+    }
+
+    let mut known: Vec<Option<&str>> = vec![None; next_place];
+    for (i, (stack, &label)) in places.iter().zip(&labels).enumerate() {
+        match (stack.last(), label) {
+            (None, "") => {}
+            (Some(place), label) if !label.is_empty() => {
+                known[place.parse::<usize>().unwrap()] = Some(label)
+            }
+            (None, _) => return println!("  No layout fits, stack {} always ends up empty", i + 1),
+            (Some(_), _) => return println!("  No layout fits, stack {} is never empty", i + 1),
+        }
+    }
+
+    // Shown as ? where any crate would do
+    let template: StackSet = starting_places
+        .iter()
+        .map(|stack| {
+            stack
+                .iter()
+                .map(|place| known[place.parse::<usize>().unwrap()].unwrap_or("?").to_string())
+                .collect()
+        })
+        .collect();
+    println!("Starting layouts that give {message:?}, where ? could be any crate:");
+    print!("{}", render_stacks(&template, None));
+    let num_free = known.iter().filter(|letter| letter.is_none()).count();
+    println!("{} crates are fixed by the message and {num_free} are free", next_place - num_free);
 }
 
-const NUM_STACKS: usize = 9;
+pub fn solve_short() {
+    print_answer(1, format_args!("Moved one at a time: {}", solve_short_impl(false)));
+    print_answer(2, format_args!("Moved multiple at a time: {}", solve_short_impl(true)));
+}
 
-type Stack = Vec<u8>;
-type StackSet = [Stack; NUM_STACKS];
+// Crate labels are usually one letter, but can be longer
+type Stack = Vec<String>;
+type StackSet = Vec<Stack>;
 
 struct Move {
     number: usize,
@@ -23,30 +316,119 @@ struct Move {
     dest_stack: usize,
 }
 
-// Takes any iterator whose item can be taken as a &str
+// A crane that can lift up to `capacity` crates at once. A move of more than that takes several
+// grabs off the top of the source stack, each of which keeps its crates in order
+struct CrateMover {
+    // No limit if None
+    capacity: Option<usize>,
+}
+
+impl CrateMover {
+    fn from_params() -> Self {
+        let capacity = match CAPACITY.get::<String>().as_str() {
+            "all" => None,
+            value => {
+                let capacity = value.parse().expect("Invalid value for parameter capacity");
+                assert!(capacity > 0, "The crane has to lift at least one crate");
+                Some(capacity)
+            }
+        };
+        Self { capacity }
+    }
+
+    // How many crates each grab of an `n` crate move lifts, in order
+    fn grab_sizes(&self, n: usize) -> Vec<usize> {
+        let capacity = self.capacity.unwrap_or(n).max(1);
+        let mut sizes = vec![capacity; n / capacity];
+        sizes.extend(Some(n % capacity).filter(|&rest| rest > 0));
+        sizes
+    }
+
+    // Returns the number of grabs the move took
+    fn apply(&self, crate_stacks: &mut StackSet, mv: &Move) -> usize {
+        let [src, dest] =
+            crate_stacks.get_many_mut([mv.src_stack, mv.dest_stack]).expect("Bad stack indices");
+        assert!(mv.number <= src.len(), "Not enough crates to move");
+
+        let sizes = self.grab_sizes(mv.number);
+        for &grab in &sizes {
+            dest.extend(src.drain((src.len() - grab)..));
+        }
+        sizes.len()
+    }
+
+    // Puts the crates back where they came from. The last grab ended up on top, so it goes back
+    // first, underneath where the earlier grabs go
+    fn undo(&self, crate_stacks: &mut StackSet, mv: &Move) -> Result<(), String> {
+        let [src, dest] = crate_stacks
+            .get_many_mut([mv.src_stack, mv.dest_stack])
+            .map_err(|_| format!("it moves stack {} onto itself", mv.src_stack + 1))?;
+        if mv.number > dest.len() {
+            return Err(format!(
+                "stack {} has {} crates, but the move left {} there",
+                mv.dest_stack + 1,
+                dest.len(),
+                mv.number
+            ));
+        }
+
+        for grab in self.grab_sizes(mv.number).into_iter().rev() {
+            src.extend(dest.drain((dest.len() - grab)..));
+        }
+        Ok(())
+    }
+}
+
+// Takes any iterator whose item can be taken as a &str, and reads up to and including the row of
+// stack numbers. The numbers say how many stacks there are and where each one's column is, so
+// crate lines can be any length and labels any width
 fn parse_crate_stacks<S: AsRef<str>>(lines: &mut impl Iterator<Item = S>) -> StackSet {
-    const EMPTY_VEC: Stack = Vec::new();
-    let mut stacks = [EMPTY_VEC; NUM_STACKS];
-    loop {
-        // Explicitly bind the item returned by the iterator so that it lives long enough
-        let orig_type_line = lines.next().expect("Unexpected input end");
-
-        let line = orig_type_line.as_ref();
-        if line.starts_with(" 1") {
-            for stack in &mut stacks {
-                stack.reverse();
+    let mut crate_lines = Vec::new();
+    let columns = loop {
+        let line = lines.next().expect("Unexpected input end");
+        let line = line.as_ref();
+
+        let tokens = split_tokens(line);
+        if !tokens.is_empty() && tokens.iter().all(|(_, token)| token.parse::<usize>().is_ok()) {
+            for (i, (_, token)) in tokens.iter().enumerate() {
+                assert!(token.parse() == Ok(i + 1), "Expected stacks numbered from 1 in order");
             }
-            break stacks;
+            break tokens.into_iter().map(|(mid, _)| mid).collect::<Vec<_>>();
+        }
+        crate_lines.push(line.to_string());
+    };
+
+    // Going from the bottom up, each crate lands on the stack whose number is closest to it
+    let mut stacks = vec![Stack::new(); columns.len()];
+    for line in crate_lines.iter().rev() {
+        for (mid, token) in split_tokens(line) {
+            let label = token
+                .strip_prefix('[')
+                .and_then(|token| token.strip_suffix(']'))
+                .expect("Malformed crate line");
+            let stack = (0..columns.len()).min_by_key(|&i| columns[i].abs_diff(mid)).unwrap();
+            stacks[stack].push(label.to_string());
         }
+    }
+    stacks
+}
 
-        let chars = &line.as_bytes()[1..];
-        for (i, stack) in stacks.iter_mut().enumerate() {
-            let c = *chars.get(i * 4).expect("Malformed crate line");
-            if (c as char) != ' ' {
-                stack.push(c);
+// Splits a line on spaces, giving each piece with the position of its middle (doubled, to keep
+// it whole)
+fn split_tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        match (c == ' ', start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                tokens.push((s + i - 1, &line[s..i]));
+                start = None;
             }
+            _ => {}
         }
     }
+    tokens
 }
 
 fn parse_move_line(line: &str) -> Move {
@@ -63,10 +445,7 @@ fn parse_move_line(line: &str) -> Move {
 }
 
 fn collect_message(crate_stacks: &StackSet) -> String {
-    crate_stacks
-        .iter()
-        .map(|stack| stack.last().map(|&c| c as char).unwrap_or(' '))
-        .collect::<String>()
+    crate_stacks.iter().map(|stack| stack.last().map_or(" ", |label| label.as_str())).collect()
 }
 
 fn solve_part1() {
@@ -83,7 +462,7 @@ fn solve_part1() {
     }
 
     let message = collect_message(&crate_stacks);
-    println!("The message is {message}");
+    print_answer(1, format_args!("The message is {message}"));
 }
 
 fn solve_part2() {
@@ -100,20 +479,13 @@ fn solve_part2() {
     }
 
     let message = collect_message(&crate_stacks);
-    println!("The message is {message}");
+    print_answer(2, format_args!("The message is {message}"));
 }
 
 fn solve_short_impl(multi_move: bool) -> String {
-    const EMPTY_VEC: Stack = Vec::new();
-    let input = read_to_string("./input/day05input.txt").expect("Could not read input");
+    let input = read_input_file("day05input.txt");
     let (crate_layout, commands) = input.split_once("\n\n").expect("Could not split input");
-
-    let mut crate_stacks = [EMPTY_VEC; NUM_STACKS];
-    for line in crate_layout.lines().map(|l| l.chars().collect::<Vec<_>>()) {
-        for (i, chunk) in line.chunks(4).enumerate().filter(|(_, s)| s[0] == '[') {
-            crate_stacks[i].insert(0, chunk[1] as u8);
-        }
-    }
+    let mut crate_stacks = parse_crate_stacks(&mut crate_layout.lines());
 
     for line in commands.lines() {
         let [number, from, to] = line
@@ -1,16 +1,389 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::{
+    collections::HashSet,
+    hint::black_box,
+    io::Read,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    fatal_error,
+    helpers::{print_answer, read_input_bytes, read_input_file, stream_input, Param},
+};
+
+pub const NOTES: &str =
+    "Part 1 packs the four-character window into a u32 and tests for duplicate \
+    bytes with bit tricks. Part 2 keeps a running count of duplicated letters as the window \
+    slides.";
 
 pub fn solve() {
-    println!("Packet found starting at {}", solve_part1());
-    println!("Message found starting at {}", solve_part2());
+    // Guaranteed ASCII
+    let bytes = read_input_file("day06input.txt").into_bytes();
+    let bytes = trim_line_ending(&bytes);
+    print_answer(1, format_args!("Packet found starting at {}", solve_part1(bytes)));
+    print_answer(2, format_args!("Message found starting at {}", solve_part2(bytes)));
 }
 
 pub fn solve_short() {
-    println!("Packet found starting at {}", solve_short_impl::<4>());
-    println!("Message found starting at {}", solve_short_impl::<14>());
+    let bytes = read_input_file("day06input.txt").into_bytes();
+    print_answer(1, format_args!("Packet found starting at {}", solve_short_impl::<4>(&bytes)));
+    print_answer(2, format_args!("Message found starting at {}", solve_short_impl::<14>(&bytes)));
+}
+
+// The input can end with a line ending, which isn't part of the signal. Only the one is taken off,
+// the same as `scan_markers` does
+fn trim_line_ending(bytes: &[u8]) -> &[u8] {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes.strip_suffix(b"\r").unwrap_or(bytes)
+}
+
+// Works on any bytes, for each window length from the `windows` parameter
+pub fn solve_skip_ahead() {
+    const MAX_WINDOW_LEN: usize = 64;
+
+    let window_lens = window_lens();
+    if let Some(too_long) = window_lens.iter().find(|&&len| len > MAX_WINDOW_LEN) {
+        fatal_error(&format!(
+            "Windows can be at most {MAX_WINDOW_LEN} bytes long for skipping ahead, found {too_long}"
+        ));
+    }
+
+    let bytes = read_input_bytes("day06input.txt");
+    let bytes = trim_line_ending(&bytes);
+
+    for window_len in window_lens {
+        match find_marker_fast(bytes, window_len) {
+            Some(position) => println!("Window {window_len}: marker found at {position}"),
+            None => println!("Window {window_len}: no marker"),
+        }
+    }
+}
+
+// Checks each candidate window from its end backwards, keeping a 256-bit set of the bytes seen.
+// Finding a repeat at `i` rules out every window that starts at or before `i`, so the next
+// candidate starts just after it. When repeats tend to be close together, like in the puzzle input,
+// that skips most of the stream. When they're spread out it rechecks nearly a whole window for each
+// step forward, and sliding counts are much faster
+fn find_marker_fast(bytes: &[u8], window_len: usize) -> Option<usize> {
+    let mut start = 0;
+    while start + window_len <= bytes.len() {
+        let mut seen = [0u64; 4];
+        let mut repeat = None;
+        for i in (start..start + window_len).rev() {
+            let (word, bit) = ((bytes[i] >> 6) as usize, 1u64 << (bytes[i] & 63));
+            if seen[word] & bit != 0 {
+                repeat = Some(i);
+                break;
+            }
+            seen[word] |= bit;
+        }
+
+        match repeat {
+            Some(i) => start = i + 1,
+            None => return Some(start + window_len),
+        }
+    }
+    None
+}
+
+pub const BENCH_TIME: Param = Param {
+    name: "bench_ms",
+    default: "200",
+    description: "How long to keep rerunning each implementation in the benchmark, in milliseconds",
+};
+
+// Times each way of finding the markers, on the puzzle input and on a longer stream where the
+// marker only comes at the very end. That second one is the worst case for skipping ahead, since
+// every repeat is as far back in the window as it can be. Every finder gives the position just
+// after the marker's window
+pub fn solve_bench() {
+    let input = read_input_file("day06input.txt").into_bytes();
+    let bytes = trim_line_ending(&input).to_vec();
+
+    // A cycle of one fewer letters than the window, so nothing matches until the tail
+    let late_marker = |window_len: usize| {
+        let mut bytes: Vec<u8> =
+            (b'a'..b'a' + window_len as u8 - 1).cycle().take(1 << 20).collect();
+        bytes.extend(b'a'..b'a' + window_len as u8);
+        bytes
+    };
+
+    type Finder = fn(&[u8]) -> usize;
+    let packet_finders: [(&str, Finder); 4] = [
+        ("SWAR (standard part 1)", solve_part1),
+        ("HashSet (short)", solve_short_impl::<4>),
+        ("sliding counts (stream)", |bytes| find_marker_streaming(bytes, 4)),
+        ("skip-ahead", |bytes| find_marker_fast(bytes, 4).unwrap()),
+    ];
+    let message_finders: [(&str, Finder); 4] = [
+        ("sliding counts (standard part 2)", solve_part2),
+        ("HashSet (short)", solve_short_impl::<14>),
+        ("sliding counts (stream)", |bytes| find_marker_streaming(bytes, 14)),
+        ("skip-ahead", |bytes| find_marker_fast(bytes, 14).unwrap()),
+    ];
+
+    let datasets =
+        [("puzzle input", bytes.clone(), bytes), ("late marker", late_marker(4), late_marker(14))];
+    for (name, packet_bytes, message_bytes) in &datasets {
+        println!("{name} ({} bytes):", message_bytes.len());
+        println!("  {:>6}  {:<34} {:>8} {:>12}", "Window", "Implementation", "Answer", "Time");
+        for (window_len, finders, bytes) in
+            [(4, &packet_finders, packet_bytes), (14, &message_finders, message_bytes)]
+        {
+            let mut answers = Vec::new();
+            for (name, finder) in finders {
+                let (answer, time) = time_finder(*finder, bytes);
+                println!("  {window_len:>6}  {name:<34} {answer:>8} {time:>12.2?}");
+                answers.push(answer);
+            }
+            if answers.iter().any(|&answer| answer != answers[0]) {
+                println!("  Warning: the implementations disagree on window {window_len}");
+            }
+        }
+    }
+    println!(
+        "Skip-ahead is quickest when repeats are close together, as in the puzzle input, and \
+        slowest when they're a window apart, as in the late marker stream"
+    );
+}
+
+// Reruns it for the `bench_ms` parameter's time, returning its answer and average time
+fn time_finder(finder: fn(&[u8]) -> usize, bytes: &[u8]) -> (usize, Duration) {
+    let budget = Duration::from_millis(BENCH_TIME.get());
+    let start = Instant::now();
+    let mut runs = 0u32;
+    let mut answer = 0;
+    while runs == 0 || start.elapsed() < budget {
+        answer = black_box(finder(black_box(bytes)));
+        runs += 1;
+    }
+    (answer, start.elapsed() / runs)
+}
+
+// The first marker from a `MarkerDetector`, for comparing it with the rest
+fn find_marker_streaming(bytes: &[u8], window_len: usize) -> usize {
+    let mut detector = MarkerDetector::new(window_len, 0);
+    bytes.iter().find_map(|&b| detector.push(b)).expect("Marker not found")
+}
+
+pub const WINDOWS: Param = Param {
+    name: "windows",
+    default: "4,14",
+    description: "Comma-separated window lengths to look for markers of",
+};
+
+pub const LIST_MARKERS: Param = Param {
+    name: "list",
+    default: "false",
+    description: "Whether to print every marker position rather than a summary",
+};
+
+// The lengths from the `windows` parameter. Anything that can't be a window length ends the run
+// with an error, whichever implementation asked
+fn window_lens() -> Vec<usize> {
+    WINDOWS
+        .get::<String>()
+        .split(',')
+        .map(|len| match len.trim().parse() {
+            Ok(0) => fatal_error("Windows need at least one byte"),
+            Ok(len) => len,
+            Err(_) => fatal_error(&format!("Malformed window length {len:?}")),
+        })
+        .collect()
+}
+
+// Finds every marker for each window length in one pass over the input, which is never held in
+// memory as a whole, so it can be as big as it likes
+pub fn solve_stream() {
+    let list: bool = LIST_MARKERS.get();
+
+    let window_lens = window_lens();
+    let mut detectors: Vec<MarkerDetector> =
+        window_lens.iter().map(|&len| MarkerDetector::new(len, 0)).collect();
+    let mut summaries = vec![(None, None, 0usize); detectors.len()];
+
+    let result = scan_markers(stream_input("day06input.txt"), &mut detectors, |i, position| {
+        if list {
+            println!("Window {}: marker at {position}", window_lens[i]);
+        }
+        let (first, last, count) = &mut summaries[i];
+        first.get_or_insert(position);
+        *last = Some(position);
+        *count += 1;
+    });
+    if let Err(e) = result {
+        fatal_error(&format!("Could not read input: {e}"));
+    }
+
+    for (window_len, (first, last, count)) in window_lens.iter().zip(summaries) {
+        match (first, last) {
+            (Some(first), Some(last)) => println!(
+                "Window {window_len}: first marker at {first}, last at {last}, {count} in all"
+            ),
+            _ => println!("Window {window_len}: no markers"),
+        }
+    }
+}
+
+// Tracks the last `window_len` bytes in a ring buffer, along with how many times each byte value
+// appears in it and how many repeats there are (every copy of a value after the first). So memory
+// use depends only on the window length, however long the stream is. A marker normally needs no
+// repeats at all, but noisy signals can allow up to `max_repeats`
+struct MarkerDetector {
+    window_len: usize,
+    max_repeats: usize,
+    ring: Vec<u8>,
+    counts: [u32; 256],
+    // The window's length minus the number of different values in it
+    num_repeats: usize,
+    // Bytes seen so far
+    position: usize,
+}
+
+impl MarkerDetector {
+    fn new(window_len: usize, max_repeats: usize) -> Self {
+        assert!(window_len > 0, "Windows need at least one byte");
+        Self {
+            window_len,
+            max_repeats,
+            ring: vec![0; window_len],
+            counts: [0; 256],
+            num_repeats: 0,
+            position: 0,
+        }
+    }
+
+    // Returns the marker position (the number of bytes up to the end of the window) if the last
+    // `window_len` bytes have no more than `max_repeats` repeats
+    fn push(&mut self, b: u8) -> Option<usize> {
+        let ring_idx = self.position % self.window_len;
+        if self.position >= self.window_len {
+            let count = &mut self.counts[self.ring[ring_idx] as usize];
+            *count -= 1;
+            if *count >= 1 {
+                self.num_repeats -= 1;
+            }
+        }
+
+        self.ring[ring_idx] = b;
+        let count = &mut self.counts[b as usize];
+        *count += 1;
+        if *count >= 2 {
+            self.num_repeats += 1;
+        }
+
+        self.position += 1;
+        let is_marker = self.num_repeats <= self.max_repeats;
+        (self.position >= self.window_len && is_marker).then_some(self.position)
+    }
+}
+
+// Feeds the stream through every detector a buffer at a time, calling `on_marker` with the
+// detector's index and the position of each marker found, as a byte offset into the stream. The
+// line ending at the very end isn't part of the signal, so the latest line ending (\n, \r\n or \r)
+// is held back until anything else comes after it, and dropped if nothing does. That's never more
+// than two bytes, however many blank lines there are
+fn scan_markers(
+    mut reader: impl Read,
+    detectors: &mut [MarkerDetector],
+    mut on_marker: impl FnMut(usize, usize),
+) -> std::io::Result<()> {
+    let mut push = |b: u8| {
+        for (i, detector) in detectors.iter_mut().enumerate() {
+            if let Some(position) = detector.push(b) {
+                on_marker(i, position);
+            }
+        }
+    };
+
+    let mut buffer = [0u8; 64 * 1024];
+    let mut held_back: Option<&[u8]> = None;
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        for &b in &buffer[..n] {
+            held_back = match (held_back, b) {
+                (Some(b"\r"), b'\n') => Some(b"\r\n"),
+                (held, b'\n' | b'\r') => {
+                    held.into_iter().flatten().copied().for_each(&mut push);
+                    Some(if b == b'\n' { b"\n" } else { b"\r" })
+                }
+                (held, b) => {
+                    held.into_iter().flatten().copied().for_each(&mut push);
+                    push(b);
+                    None
+                }
+            };
+        }
+    }
+}
+
+pub const MAX_REPEATS: Param = Param {
+    name: "max_repeats",
+    default: "1",
+    description: "How many repeats the noisy implementation allows in a window, counting every \
+        copy of a character after its first",
+};
+
+// For noisy signals: the first window of each length with at most `max_repeats` repeated
+// characters, and the longest stretch with no repeats at all
+pub fn solve_noisy() {
+    let max_repeats: usize = MAX_REPEATS.get();
+
+    let bytes = read_input_bytes("day06input.txt");
+    let bytes = trim_line_ending(&bytes);
+
+    for window_len in window_lens() {
+        let mut detector = MarkerDetector::new(window_len, max_repeats);
+        let allowed = match max_repeats {
+            1 => "1 repeat".to_string(),
+            n => format!("{n} repeats"),
+        };
+        match bytes.iter().find_map(|&b| detector.push(b)) {
+            Some(position) => {
+                println!("Window {window_len} with at most {allowed}: first at {position}")
+            }
+            None => println!("Window {window_len} with at most {allowed}: none"),
+        }
+    }
+
+    match longest_distinct_run(bytes) {
+        Some((start, len)) => println!(
+            "Longest run with no repeats is {len} long, from {} to {}",
+            start + 1,
+            start + len
+        ),
+        None => println!("The signal is empty"),
+    }
 }
 
-fn solve_part1() -> usize {
+// Returns where the run starts (counting from 0) and its length. Ties go to the earliest. Moving
+// along, the run ending at each byte starts just after that byte's previous appearance, or where
+// the last run started if that's later
+fn longest_distinct_run(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut last_seen = [None; 256];
+    let mut start = 0;
+    let mut longest: Option<(usize, usize)> = None;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        if let Some(prev) = last_seen[b as usize] {
+            start = start.max(prev + 1);
+        }
+        last_seen[b as usize] = Some(i);
+
+        let len = i + 1 - start;
+        if !matches!(longest, Some((_, longest_len)) if longest_len >= len) {
+            longest = Some((start, len));
+        }
+    }
+    longest
+}
+
+fn solve_part1(bytes: &[u8]) -> usize {
     // Returns nonzero if there was a zero byte, or zero otherwise.
     // We don't convert to bool yet, for speed
     #[inline(always)]
@@ -36,15 +409,11 @@ fn solve_part1() -> usize {
         println!("Packet found starting at {idx}");
     }
 
-    // Guaranteed ASCII
-    let bytes =
-        read_to_string("./input/day06input.txt").expect("Could not open input file").into_bytes();
-
     // We pack each of the four characters we test into a u32, for quick testing.
     // Special-case check the first four, for loop simplicity
     let mut u = u32::from_ne_bytes(bytes[0..4].try_into().expect("Input not long enough"));
     if all_bytes_different(u) {
-        return 0;
+        return 4;
     }
 
     // Check the remaining characters
@@ -59,21 +428,12 @@ fn solve_part1() -> usize {
     unreachable!("Packet not found");
 }
 
-fn solve_part2() -> usize {
+fn solve_part2(bytes: &[u8]) -> usize {
     const MESSAGE_SIZE: usize = 14;
     const ALPHA_SIZE: usize = 26;
 
-    // Guaranteed ASCII
-    let mut bytes =
-        read_to_string("./input/day06input.txt").expect("Could not open input file").into_bytes();
-
-    // There is an unwanted newline at the end
-    bytes.pop();
-
-    // All the characters are lowercase letters. This remaps them into the range [0, ALPHA_SIZE)
-    for b in bytes.iter_mut() {
-        *b -= 97;
-    }
+    // All the characters are lowercase letters. This maps them into the range [0, ALPHA_SIZE)
+    let letter = |b: u8| (b - b'a') as usize;
 
     let mut counts = [0u8; ALPHA_SIZE];
     let mut num_duplicates = 0;
@@ -82,7 +442,7 @@ fn solve_part2() -> usize {
     // Initialize the counters with the first batch of characters
     message.copy_from_slice(&bytes[0..MESSAGE_SIZE]);
     for &b in message.iter() {
-        let count = &mut counts[b as usize];
+        let count = &mut counts[letter(b)];
         *count += 1;
         if *count == 2 {
             num_duplicates += 1;
@@ -91,7 +451,7 @@ fn solve_part2() -> usize {
 
     // Already done?
     if num_duplicates == 0 {
-        return 0;
+        return MESSAGE_SIZE;
     }
 
     // Check the remaining characters
@@ -101,7 +461,7 @@ fn solve_part2() -> usize {
 
         // Decrement the character we're shifting out
         let shifted_out = message[ring_idx];
-        let count = &mut counts[shifted_out as usize];
+        let count = &mut counts[letter(shifted_out)];
         *count -= 1;
         if *count == 1 {
             num_duplicates -= 1;
@@ -109,7 +469,7 @@ fn solve_part2() -> usize {
 
         // Increment the character we're shifting in
         message[ring_idx] = b;
-        let count = &mut counts[b as usize];
+        let count = &mut counts[letter(b)];
         *count += 1;
         if *count == 2 {
             num_duplicates += 1;
@@ -123,15 +483,48 @@ fn solve_part2() -> usize {
     unreachable!("Message not found");
 }
 
-fn solve_short_impl<const N: usize>() -> usize {
-    // Guaranteed ASCII
-    let bytes =
-        read_to_string("./input/day06input.txt").expect("Could not open input file").into_bytes();
-
+fn solve_short_impl<const N: usize>(bytes: &[u8]) -> usize {
     for (i, window) in bytes.array_windows::<N>().enumerate() {
         if HashSet::<u8>::from_iter((*window).into_iter()).len() == N {
-            return i;
+            return i + N;
         }
     }
     unreachable!("Not found");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn first_marker(bytes: &[u8], window_len: usize, max_repeats: usize) -> Option<usize> {
+        let mut detector = MarkerDetector::new(window_len, max_repeats);
+        bytes.iter().find_map(|&b| detector.push(b))
+    }
+
+    #[test]
+    fn identical_bytes_are_all_repeats() {
+        let bytes = [b'a'; 24];
+        assert_eq!(first_marker(&bytes, 14, 1), None);
+        assert_eq!(first_marker(&bytes, 14, 12), None);
+        assert_eq!(first_marker(&bytes, 14, 13), Some(14));
+        assert_eq!(first_marker(&bytes, 1, 0), Some(1));
+    }
+
+    #[test]
+    fn no_repeats_allowed() {
+        let bytes = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(first_marker(bytes, 4, 0), Some(7));
+        assert_eq!(first_marker(bytes, 14, 0), Some(19));
+        assert_eq!(first_marker(bytes, 4, 0), find_marker_fast(bytes, 4));
+        assert_eq!(first_marker(bytes, 14, 0), find_marker_fast(bytes, 14));
+    }
+
+    #[test]
+    fn counts_every_extra_copy() {
+        // Three b's make two repeats, even though only one value is repeated
+        assert_eq!(first_marker(b"abbb", 4, 1), None);
+        assert_eq!(first_marker(b"abbb", 4, 2), Some(4));
+        // Sliding out the extra copies brings the count back down
+        assert_eq!(first_marker(b"bbbacd", 4, 0), Some(6));
+    }
+}
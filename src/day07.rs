@@ -1,17 +1,109 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer, read_command, Param};
+
+pub const NOTES: &str = "Rebuilds the directory tree as an arena from the terminal log, then \
+    totals up subtree sizes recursively.";
 
 pub fn solve() {
     let directory_tree = traverse_command_history();
-    println!("Sum is {}", sum_small_sizes(&directory_tree));
-    println!("Smallest deletion is {}", find_deletion_candidate_size(&directory_tree));
+    print_answer(1, format_args!("Sum is {}", sum_small_sizes(&directory_tree)));
+    print_answer(
+        2,
+        format_args!("Smallest deletion is {}", find_deletion_candidate_size(&directory_tree)),
+    );
+}
+
+// Prints the whole hierarchy like `tree -h`, with each directory's total size
+pub fn show_tree() {
+    print!("{}", render_tree(&traverse_command_history(), 0));
+}
+
+pub const LARGEST: Param = Param {
+    name: "largest",
+    default: "5",
+    description: "How many of the largest files the query implementation lists",
+};
+
+pub const GLOB: Param = Param {
+    name: "glob",
+    default: "",
+    description: "List the files matching this pattern, with * and ? as wildcards. It's matched \
+        against the full path if it contains a /, otherwise against the file name",
+};
+
+pub const AT_LEAST: Param = Param {
+    name: "at_least",
+    default: "",
+    description: "List the directories with a total size of at least this many bytes",
+};
+
+pub const AT_MOST: Param = Param {
+    name: "at_most",
+    default: "",
+    description: "List the directories with a total size of at most this many bytes",
+};
+
+// Answers questions about the filesystem, picked with the parameters above
+pub fn solve_query() {
+    let directory_tree = traverse_command_history();
+
+    let largest: usize = LARGEST.get();
+    let mut files = all_files(&directory_tree);
+    files.sort_by(|a, b| b.1.size.cmp(&a.1.size).then_with(|| a.0.cmp(&b.0)));
+    println!("Largest {} files:", largest.min(files.len()));
+    for (path, file) in files.iter().take(largest) {
+        println!("  {:>10}  {path}", file.size);
+    }
+
+    let glob: String = GLOB.get();
+    if !glob.is_empty() {
+        let matching: Vec<_> = files
+            .iter()
+            .filter(|(path, file)| {
+                glob_match(&glob, if glob.contains('/') { path } else { &file.name })
+            })
+            .collect();
+        println!("{} files match {glob}:", matching.len());
+        for (path, file) in matching {
+            println!("  {:>10}  {path}", file.size);
+        }
+    }
+
+    let thresholds = [
+        (AT_LEAST, "at least", usize::ge as fn(&usize, &usize) -> bool),
+        (AT_MOST, "at most", usize::le),
+    ];
+    for (param, description, keep) in thresholds {
+        let threshold: String = param.get();
+        if threshold.is_empty() {
+            continue;
+        }
+        let threshold: usize = threshold.parse().expect("Malformed size threshold");
+
+        let matching: Vec<usize> = (0..directory_tree.len())
+            .filter(|&idx| keep(&directory_tree[idx].subtree_file_total, &threshold))
+            .collect();
+        println!("{} directories of {description} {threshold}:", matching.len());
+        for idx in matching {
+            let size = directory_tree[idx].subtree_file_total;
+            println!("  {size:>10}  {}", directory_path(&directory_tree, idx));
+        }
+    }
+}
+
+struct File {
+    name: String,
+    size: usize,
 }
 
 struct Directory {
     name: String,
+    files: Vec<File>,
     local_file_total: usize,
     subtree_file_total: usize,
     parent: usize,
     child_dirs: Vec<usize>,
+    // Whether an `ls` has shown what's in it
+    listed: bool,
 }
 
 fn fill_subtree_sizes(directory_tree: &mut [Directory]) {
@@ -33,87 +125,464 @@ fn fill_subtree_sizes(directory_tree: &mut [Directory]) {
     recurse(directory_tree, 0, 0);
 }
 
-fn change_directory(directory_tree: &[Directory], cur_idx: usize, dir_name: &str) -> usize {
-    match dir_name {
-        "/" => 0,
-        ".." => directory_tree[cur_idx].parent,
-        _ => *directory_tree[cur_idx]
-            .child_dirs
-            .iter()
-            .find(|&&idx| directory_tree[idx].name == dir_name)
-            .expect("Unknown subdirectory"),
+// Follows a path one segment at a time. Directories that haven't been listed yet are created as
+// they're reached
+fn change_directory(directory_tree: &mut Vec<Directory>, cur_idx: usize, path: &str) -> usize {
+    let mut idx = if path.starts_with('/') { 0 } else { cur_idx };
+    for segment in path.split('/') {
+        idx = match segment {
+            "" | "." => idx,
+            ".." => directory_tree[idx].parent,
+            name => match find_child(directory_tree, idx, name) {
+                Some(child) => child,
+                None => add_directory(directory_tree, idx, name),
+            },
+        };
+    }
+    idx
+}
+
+fn find_child(directory_tree: &[Directory], cur_idx: usize, name: &str) -> Option<usize> {
+    directory_tree[cur_idx].child_dirs.iter().copied().find(|&idx| directory_tree[idx].name == name)
+}
+
+// Like change_directory, but only follows directories that exist
+fn find_directory(directory_tree: &[Directory], cur_idx: usize, path: &str) -> Option<usize> {
+    let mut idx = if path.starts_with('/') { 0 } else { cur_idx };
+    for segment in path.split('/') {
+        idx = match segment {
+            "" | "." => idx,
+            ".." => directory_tree[idx].parent,
+            name => find_child(directory_tree, idx, name)?,
+        };
     }
+    Some(idx)
 }
 
+fn add_directory(directory_tree: &mut Vec<Directory>, parent: usize, name: &str) -> usize {
+    let child_idx = directory_tree.len();
+    directory_tree.push(Directory {
+        name: name.to_string(),
+        files: Vec::new(),
+        local_file_total: 0,
+        subtree_file_total: 0,
+        parent,
+        child_dirs: vec![],
+        listed: false,
+    });
+    directory_tree[parent].child_dirs.push(child_idx);
+    child_idx
+}
+
+// Lines of the log, numbered from 1
+type LogLines<I> = std::iter::Peekable<std::iter::Zip<std::ops::RangeFrom<usize>, I>>;
+
+// Adds whatever the listing shows that isn't known yet, so listing a directory again changes
+// nothing. Anything that disagrees with what's already known (a file with another size, a name
+// that's a file in one place and a directory in another, or an entry that has since gone) becomes
+// a warning, and the first version is kept
 fn list_directory(
     directory_tree: &mut Vec<Directory>,
     cur_idx: usize,
-    lines: &mut impl Iterator<Item = String>,
-) -> String {
-    loop {
-        let cur_line = lines.next().unwrap_or_default();
-        if cur_line.is_empty() || cur_line.starts_with("$ ") {
-            return cur_line;
-        }
+    ls_line_number: usize,
+    lines: &mut LogLines<impl Iterator<Item = String>>,
+    warnings: &mut Vec<String>,
+) {
+    let path = directory_path(directory_tree, cur_idx);
+    let mut seen = Vec::new();
 
-        let (file_tag, file_name) = cur_line.split_once(' ').expect("Malformed ls line");
-        match file_tag {
-            "dir" => {
-                let child_idx = directory_tree.len();
-                directory_tree.push(Directory {
-                    name: file_name.to_string(),
-                    local_file_total: 0,
-                    subtree_file_total: 0,
-                    parent: cur_idx,
-                    child_dirs: vec![],
-                });
-                directory_tree[cur_idx].child_dirs.push(child_idx);
+    while let Some((line_number, line)) = lines.next_if(|(_, line)| !line.starts_with("$ ")) {
+        let Some((file_tag, name)) = line.split_once(' ') else {
+            warnings.push(format!("Line {line_number}: can't make sense of {line:?}"));
+            continue;
+        };
+        seen.push(name.to_string());
+
+        let existing_dir = find_child(directory_tree, cur_idx, name);
+        let existing_file = directory_tree[cur_idx].files.iter().find(|file| file.name == name);
+        match (file_tag, existing_dir, existing_file) {
+            ("dir", Some(_), _) => {}
+            ("dir", None, Some(_)) => warnings.push(format!(
+                "Line {line_number}: {name} in {path} was a file, now listed as a directory"
+            )),
+            ("dir", None, None) => {
+                add_directory(directory_tree, cur_idx, name);
             }
-            file_size => {
-                directory_tree[cur_idx].local_file_total +=
-                    file_size.parse::<usize>().expect("Invalid file size");
+            (file_size, existing_dir, existing_file) => {
+                let Ok(size) = file_size.parse::<usize>() else {
+                    warnings.push(format!("Line {line_number}: invalid file size {file_size:?}"));
+                    continue;
+                };
+                match (existing_dir, existing_file) {
+                    (Some(_), _) => warnings.push(format!(
+                        "Line {line_number}: {name} in {path} was a directory, now listed as a file"
+                    )),
+                    (None, Some(file)) if file.size != size => warnings.push(format!(
+                        "Line {line_number}: {name} in {path} was {} bytes, now listed as {size}",
+                        file.size
+                    )),
+                    (None, Some(_)) => {}
+                    (None, None) => {
+                        directory_tree[cur_idx].files.push(File { name: name.to_string(), size })
+                    }
+                }
             }
         }
     }
+
+    let cur_dir = &directory_tree[cur_idx];
+    if cur_dir.listed {
+        let child_names = cur_dir.child_dirs.iter().map(|&idx| &directory_tree[idx].name);
+        let file_names = cur_dir.files.iter().map(|file| &file.name);
+        for name in child_names.chain(file_names).filter(|name| !seen.contains(name)) {
+            warnings.push(format!("Line {ls_line_number}: {path} listed again without {name}"));
+        }
+    }
+    directory_tree[cur_idx].listed = true;
 }
 
-fn traverse_command_history() -> Vec<Directory> {
-    let mut lines = iterate_file_lines("day07input.txt");
+// Replays the terminal log, collecting anything odd in it as warnings rather than giving up
+fn replay_command_history() -> (Vec<Directory>, Vec<String>) {
+    let mut lines = (1..).zip(iterate_file_lines("day07input.txt")).peekable();
+    let mut warnings = Vec::new();
 
     // Populate the root directory immediately
     let mut directory_tree = vec![Directory {
         name: "/".to_string(),
+        files: Vec::new(),
         local_file_total: 0,
         subtree_file_total: 0,
         parent: 0,
         child_dirs: Vec::new(),
+        listed: false,
     }];
     let mut cur_directory = 0usize;
 
-    let mut command = lines.next().expect("Unexpected EOF");
-    while !command.is_empty() {
-        assert!(command.starts_with("$ "), "Expected line to be a command");
-        let (cmd_type, cmd_rest) = command[2..].split_at(2);
+    while let Some((line_number, command)) = lines.next() {
+        let Some(command) = command.strip_prefix("$ ") else {
+            warnings.push(format!("Line {line_number}: expected a command, found {command:?}"));
+            continue;
+        };
 
-        match cmd_type {
-            "cd" => {
-                let dir_name = &cmd_rest[1..];
-                cur_directory = change_directory(&directory_tree, cur_directory, dir_name);
-                command = lines.next().expect("Unexpected EOF");
+        match command.split_once(' ').unwrap_or((command, "")) {
+            ("cd", path) => {
+                cur_directory = change_directory(&mut directory_tree, cur_directory, path);
+            }
+            ("ls", _) => {
+                list_directory(
+                    &mut directory_tree,
+                    cur_directory,
+                    line_number,
+                    &mut lines,
+                    &mut warnings,
+                );
             }
-            "ls" => {
-                command = list_directory(&mut directory_tree, cur_directory, &mut lines);
+            _ => {
+                warnings.push(format!("Line {line_number}: skipped unknown command {command:?}"));
+                while lines.next_if(|(_, line)| !line.starts_with("$ ")).is_some() {}
             }
-            _ => panic!("Unknown command"),
+        }
+    }
+
+    // Directories only reached with cd were never listed, so nobody knows what's in them
+    for idx in 0..directory_tree.len() {
+        let cur_dir = &directory_tree[idx];
+        if !cur_dir.listed {
+            warnings.push(format!("{} was never listed", directory_path(&directory_tree, idx)));
         }
     }
 
     // Now traverse the tree and calculate subtree total sizes
+    for dir in &mut directory_tree {
+        dir.local_file_total = dir.files.iter().map(|file| file.size).sum();
+    }
     fill_subtree_sizes(&mut directory_tree);
 
+    (directory_tree, warnings)
+}
+
+fn traverse_command_history() -> Vec<Directory> {
+    let (directory_tree, warnings) = replay_command_history();
+    for warning in warnings {
+        eprintln!("Warning: {warning}");
+    }
     directory_tree
 }
 
+// Replays the log and reports anything in it that doesn't add up
+pub fn solve_check() {
+    let (directory_tree, warnings) = replay_command_history();
+    let num_files: usize = directory_tree.iter().map(|dir| dir.files.len()).sum();
+    println!("Found {} directories and {num_files} files", directory_tree.len());
+
+    if warnings.is_empty() {
+        println!("The log is consistent");
+    } else {
+        println!("{} problems:", warnings.len());
+        for warning in warnings {
+            println!("  {warning}");
+        }
+    }
+}
+
+const SHELL_HELP: &str = "\
+Commands:
+  cd [PATH]                 Change directory, or go back to / if no path is given
+  ls [PATH]                 List a directory's subdirectories and files with their sizes
+  pwd                       Print the current directory
+  du [-s] [PATH]            Total size of every directory under PATH, or only of PATH with -s
+  find [PATH] [-name GLOB]  Every directory and file under PATH, or those whose name matches
+  tree [PATH]               Draw PATH and everything under it
+  help                      Show this message
+  quit                      Leave";
+
+// Walks around the rebuilt filesystem from a prompt. Paths work the way they do in the log, so
+// they can be absolute or relative and use . and .., but nothing here changes the tree. It reads
+// commands from stdin, so it's a command of its own rather than an implementation that the repl,
+// watch or serve would run without one
+pub fn shell() {
+    let directory_tree = traverse_command_history();
+    let mut cur_idx = 0;
+    println!("Type \"help\" for a list of commands");
+
+    while let Some(words) = read_command(&format!("{}$ ", directory_path(&directory_tree, cur_idx)))
+    {
+        let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+        run_shell_command(&directory_tree, &mut cur_idx, &words[0], &args);
+    }
+}
+
+fn run_shell_command(
+    directory_tree: &[Directory],
+    cur_idx: &mut usize,
+    command: &str,
+    args: &[&str],
+) {
+    // Without a path, commands work on the current directory
+    let here = *cur_idx;
+    let target = |path: Option<&&str>| match path {
+        None => Some(here),
+        Some(path) => {
+            let found = find_directory(directory_tree, here, path);
+            if found.is_none() {
+                println!("No such directory {path}");
+            }
+            found
+        }
+    };
+
+    match (command, args) {
+        ("help", []) => println!("{SHELL_HELP}"),
+        ("pwd", []) => println!("{}", directory_path(directory_tree, here)),
+        ("cd", []) => *cur_idx = 0,
+        ("cd", [path]) => {
+            if let Some(idx) = target(Some(path)) {
+                *cur_idx = idx;
+            }
+        }
+        ("ls", [] | [_]) => {
+            if let Some(idx) = target(args.first()) {
+                list_for_shell(directory_tree, idx);
+            }
+        }
+        ("tree", [] | [_]) => {
+            if let Some(idx) = target(args.first()) {
+                print!("{}", render_tree(directory_tree, idx));
+            }
+        }
+        ("du", ["-s"] | ["-s", _]) => {
+            if let Some(idx) = target(args.get(1)) {
+                let size = directory_tree[idx].subtree_file_total;
+                println!("{size:>10}  {}", directory_path(directory_tree, idx));
+            }
+        }
+        ("du", [] | [_]) => {
+            if let Some(idx) = target(args.first()) {
+                disk_usage(directory_tree, idx);
+            }
+        }
+        ("find", _) => {
+            let (path, glob) = match args {
+                [] => (None, None),
+                ["-name", glob] => (None, Some(*glob)),
+                [path] => (Some(path), None),
+                [path, "-name", glob] => (Some(path), Some(*glob)),
+                _ => return println!("Expected find [PATH] [-name GLOB]"),
+            };
+            if let Some(idx) = target(path) {
+                find_entries(directory_tree, idx, glob);
+            }
+        }
+        _ => println!("Unknown command, try \"help\""),
+    }
+}
+
+// Directories first, then files, like render_tree
+fn list_for_shell(directory_tree: &[Directory], idx: usize) {
+    let cur_dir = &directory_tree[idx];
+    for &child in &cur_dir.child_dirs {
+        let child_dir = &directory_tree[child];
+        println!("{:>10}  {}/", child_dir.subtree_file_total, child_dir.name);
+    }
+    for file in &cur_dir.files {
+        println!("{:>10}  {}", file.size, file.name);
+    }
+    if !cur_dir.listed {
+        println!("(never listed, so there may be more in here)");
+    }
+}
+
+// Like `du`, each directory comes after everything inside it
+fn disk_usage(directory_tree: &[Directory], idx: usize) {
+    for &child in &directory_tree[idx].child_dirs {
+        disk_usage(directory_tree, child);
+    }
+    let size = directory_tree[idx].subtree_file_total;
+    println!("{size:>10}  {}", directory_path(directory_tree, idx));
+}
+
+// Like `find`, each directory comes before everything inside it
+fn find_entries(directory_tree: &[Directory], idx: usize, glob: Option<&str>) {
+    let matches = |name: &str| !matches!(glob, Some(glob) if !glob_match(glob, name));
+
+    let cur_dir = &directory_tree[idx];
+    if matches(&cur_dir.name) {
+        println!("{}", directory_path(directory_tree, idx));
+    }
+    for file in cur_dir.files.iter().filter(|file| matches(&file.name)) {
+        println!("{}", file_path(directory_tree, idx, file));
+    }
+    for &child in &cur_dir.child_dirs {
+        find_entries(directory_tree, child, glob);
+    }
+}
+
+// The full path, like /a/e. The root is just /
+fn directory_path(directory_tree: &[Directory], idx: usize) -> String {
+    if idx == 0 {
+        return "/".to_string();
+    }
+    let parent_path = directory_path(directory_tree, directory_tree[idx].parent);
+    format!("{}/{}", parent_path.trim_end_matches('/'), directory_tree[idx].name)
+}
+
+fn file_path(directory_tree: &[Directory], dir_idx: usize, file: &File) -> String {
+    format!("{}/{}", directory_path(directory_tree, dir_idx).trim_end_matches('/'), file.name)
+}
+
+// Every file with its full path, in the order they were listed
+fn all_files(directory_tree: &[Directory]) -> Vec<(String, &File)> {
+    (0..directory_tree.len())
+        .flat_map(|idx| {
+            directory_tree[idx]
+                .files
+                .iter()
+                .map(move |file| (file_path(directory_tree, idx, file), file))
+        })
+        .collect()
+}
+
+// Sizes the way `du -h` shows them: bytes up to 1023, then K, M, G and so on, with one decimal
+// place while it's still a single digit
+fn human_size(size: usize) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if value < 10.0 {
+        format!("{value:.1}{}", UNITS[unit])
+    } else {
+        format!("{value:.0}{}", UNITS[unit])
+    }
+}
+
+// Draws the directory at `root_idx` and everything under it with tree's box-drawing lines.
+// Directories come first, then files, each in the order they were listed
+fn render_tree(directory_tree: &[Directory], root_idx: usize) -> String {
+    fn recurse(directory_tree: &[Directory], cur_idx: usize, prefix: &str, out: &mut String) {
+        let cur_dir = &directory_tree[cur_idx];
+        let entries: Vec<(String, Option<usize>)> = cur_dir
+            .child_dirs
+            .iter()
+            .map(|&child| {
+                let child_dir = &directory_tree[child];
+                (
+                    format!(
+                        "[{:>5}]  {}/",
+                        human_size(child_dir.subtree_file_total),
+                        child_dir.name
+                    ),
+                    Some(child),
+                )
+            })
+            .chain(
+                cur_dir
+                    .files
+                    .iter()
+                    .map(|file| (format!("[{:>5}]  {}", human_size(file.size), file.name), None)),
+            )
+            .collect();
+
+        for (i, (line, child)) in entries.iter().enumerate() {
+            let is_last = i + 1 == entries.len();
+            out.push_str(&format!("{prefix}{}{line}\n", if is_last { "└── " } else { "├── " }));
+            if let Some(child) = child {
+                let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+                recurse(directory_tree, *child, &child_prefix, out);
+            }
+        }
+    }
+
+    let root = &directory_tree[root_idx];
+    let mut out = format!(
+        "[{:>5}]  {}\n",
+        human_size(root.subtree_file_total),
+        directory_path(directory_tree, root_idx)
+    );
+    recurse(directory_tree, root_idx, "", &mut out);
+    out
+}
+
+// Shell-style wildcards: * matches any run of characters (including none) and ? any one
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), text.chars().collect());
+
+    // Where to go back to if the current attempt fails: just after the last *, and how far into
+    // the text it had matched up to
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the * swallow one more character and try again
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 fn sum_small_sizes(directory_tree: &[Directory]) -> usize {
     fn recurse(directory_tree: &[Directory], cur_idx: usize) -> usize {
         let cur_dir = &directory_tree[cur_idx];
@@ -131,9 +600,10 @@ fn sum_small_sizes(directory_tree: &[Directory]) -> usize {
     recurse(directory_tree, 0)
 }
 
+const TOTAL_AVAILABLE_SPACE: usize = 70000000;
+const NEEDED_SPACE: usize = 30000000;
+
 fn find_deletion_candidate_size(directory_tree: &[Directory]) -> usize {
-    const TOTAL_AVAILABLE_SPACE: usize = 70000000;
-    const NEEDED_SPACE: usize = 30000000;
     let left_to_free =
         NEEDED_SPACE - (TOTAL_AVAILABLE_SPACE - directory_tree[0].subtree_file_total);
 
@@ -156,3 +626,204 @@ fn find_deletion_candidate_size(directory_tree: &[Directory]) -> usize {
 
     recurse(directory_tree, 0, left_to_free).expect("No suitable directory")
 }
+
+pub const WITH_FILES: Param = Param {
+    name: "with_files",
+    default: "false",
+    description: "Whether the deletion planner can pick single files as well as directories",
+};
+
+// Rather than the one smallest directory that frees enough, finds the set of deletions that frees
+// enough while deleting as little as possible. Nothing chosen can be inside anything else chosen
+pub fn solve_plan() {
+    let directory_tree = traverse_command_history();
+    let with_files: bool = WITH_FILES.get();
+
+    let used_space = directory_tree[0].subtree_file_total;
+    let Some(free_space) = TOTAL_AVAILABLE_SPACE.checked_sub(used_space) else {
+        println!(
+            "The log's files take up {used_space}, more than the disk's {TOTAL_AVAILABLE_SPACE}"
+        );
+        return;
+    };
+    let Some(left_to_free) = NEEDED_SPACE.checked_sub(free_space).filter(|&left| left > 0) else {
+        println!("There's already enough free space");
+        return;
+    };
+    println!("Need to free {left_to_free}");
+
+    let single = find_deletion_candidate_size(&directory_tree);
+    let single_idx = (0..directory_tree.len())
+        .find(|&idx| directory_tree[idx].subtree_file_total == single)
+        .unwrap();
+    println!("Single directory: {single} ({})", directory_path(&directory_tree, single_idx));
+
+    // Never worth deleting more than the single directory does
+    let planner = DeletionPlanner { directory_tree: &directory_tree, with_files, limit: single };
+    let mut nothing = SumSet::new(single);
+    nothing.insert(0);
+    let root = Deletion::Directory(0);
+    let totals = planner.through(root, &nothing);
+    let best = totals.min_at_least(left_to_free).expect("The single directory is always possible");
+
+    let mut chosen = Vec::new();
+    let left_over = planner.trace(root, &nothing, best, &mut chosen);
+    assert!(left_over == 0, "Traced back to a nonzero start");
+    chosen.sort_by_key(|&deletion| std::cmp::Reverse(planner.size(deletion)));
+
+    let what = if with_files { "directories and files" } else { "directories" };
+    println!(
+        "Set of {what}: {best} in {} deletion{}, {} less than the single directory",
+        chosen.len(),
+        if chosen.len() == 1 { "" } else { "s" },
+        single - best
+    );
+    for deletion in chosen {
+        println!("  {:>10}  {}", planner.size(deletion), planner.path(deletion));
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Deletion {
+    Directory(usize),
+    // The directory, then the file's index in it
+    File(usize, usize),
+}
+
+// The totals from 0 to `limit` that some set of deletions adds up to, one bit each
+#[derive(Clone)]
+struct SumSet {
+    words: Vec<u64>,
+    limit: usize,
+}
+
+impl SumSet {
+    fn new(limit: usize) -> Self {
+        Self { words: vec![0; limit / 64 + 1], limit }
+    }
+
+    fn contains(&self, total: usize) -> bool {
+        total <= self.limit && self.words[total / 64] >> (total % 64) & 1 == 1
+    }
+
+    fn insert(&mut self, total: usize) {
+        if total <= self.limit {
+            self.words[total / 64] |= 1 << (total % 64);
+        }
+    }
+
+    // Adds `shift` to every total in `other` and puts them in this set, dropping any past the limit
+    fn union_shifted(&mut self, other: &SumSet, shift: usize) {
+        if shift > self.limit {
+            return;
+        }
+
+        let (word_shift, bit_shift) = (shift / 64, shift % 64);
+        for i in word_shift..self.words.len() {
+            let src = i - word_shift;
+            let mut word = other.words[src] << bit_shift;
+            if bit_shift > 0 && src > 0 {
+                word |= other.words[src - 1] >> (64 - bit_shift);
+            }
+            self.words[i] |= word;
+        }
+
+        let bits_in_last = self.limit % 64 + 1;
+        if bits_in_last < 64 {
+            *self.words.last_mut().unwrap() &= (1 << bits_in_last) - 1;
+        }
+    }
+
+    fn min_at_least(&self, target: usize) -> Option<usize> {
+        (target..=self.limit).find(|&total| self.contains(total))
+    }
+}
+
+// A knapsack over the tree. Working through the deletions in order, the set of totals possible so
+// far is carried along: a directory either goes as a whole, or the choice carries on through what's
+// in it. Only one set per level of the tree is kept, so to find out which deletions made the best
+// total, each directory's sets are worked out again on the way back down
+struct DeletionPlanner<'a> {
+    directory_tree: &'a [Directory],
+    with_files: bool,
+    limit: usize,
+}
+
+impl DeletionPlanner<'_> {
+    fn size(&self, deletion: Deletion) -> usize {
+        match deletion {
+            Deletion::Directory(idx) => self.directory_tree[idx].subtree_file_total,
+            Deletion::File(idx, file) => self.directory_tree[idx].files[file].size,
+        }
+    }
+
+    fn path(&self, deletion: Deletion) -> String {
+        match deletion {
+            Deletion::Directory(idx) => directory_path(self.directory_tree, idx),
+            Deletion::File(idx, file) => {
+                file_path(self.directory_tree, idx, &self.directory_tree[idx].files[file])
+            }
+        }
+    }
+
+    // What can be deleted inside a directory, other than the whole thing
+    fn contents(&self, idx: usize) -> Vec<Deletion> {
+        let cur_dir = &self.directory_tree[idx];
+        let files = if self.with_files { cur_dir.files.len() } else { 0 };
+        let dirs = cur_dir.child_dirs.iter().map(|&child| Deletion::Directory(child));
+        dirs.chain((0..files).map(|file| Deletion::File(idx, file))).collect()
+    }
+
+    // The totals possible after deciding on this deletion, given those possible before it
+    fn through(&self, deletion: Deletion, before: &SumSet) -> SumSet {
+        let mut after = match deletion {
+            Deletion::Directory(idx) => self.through_contents(idx, before),
+            Deletion::File(..) => before.clone(),
+        };
+        after.union_shifted(before, self.size(deletion));
+        after
+    }
+
+    // Each set, from before the first item in the directory to after the last
+    fn contents_sets(&self, idx: usize, before: &SumSet) -> Vec<SumSet> {
+        let mut sets = vec![before.clone()];
+        for deletion in self.contents(idx) {
+            let next = self.through(deletion, sets.last().unwrap());
+            sets.push(next);
+        }
+        sets
+    }
+
+    fn through_contents(&self, idx: usize, before: &SumSet) -> SumSet {
+        self.contents(idx)
+            .into_iter()
+            .fold(before.clone(), |set, deletion| self.through(deletion, &set))
+    }
+
+    // Given a total that `through` gives for this deletion, adds the deletions that make it up to
+    // `chosen`, and returns the part of the total that came from before
+    fn trace(
+        &self,
+        deletion: Deletion,
+        before: &SumSet,
+        total: usize,
+        chosen: &mut Vec<Deletion>,
+    ) -> usize {
+        let size = self.size(deletion);
+        if total >= size && before.contains(total - size) {
+            chosen.push(deletion);
+            return total - size;
+        }
+
+        let Deletion::Directory(idx) = deletion else {
+            // Not deleting a file leaves the total as it was
+            return total;
+        };
+        let sets = self.contents_sets(idx, before);
+        self.contents(idx)
+            .into_iter()
+            .enumerate()
+            .rev()
+            .fold(total, |total, (i, deletion)| self.trace(deletion, &sets[i], total, chosen))
+    }
+}
@@ -1,4 +1,6 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
+
+pub const NOTES: &str = "Brute-force line of sight scans in all four directions from every tree.";
 
 pub fn solve() {
     let tree_grid: Vec<Vec<u8>> =
@@ -23,8 +25,8 @@ pub fn solve() {
         }
     }
 
-    println!("Visible trees: {sum}");
-    println!("Max scenic score: {scenic_score}");
+    print_answer(1, format_args!("Visible trees: {sum}"));
+    print_answer(2, format_args!("Max scenic score: {scenic_score}"));
 }
 
 fn is_visible(tree_grid: &[Vec<u8>], x: usize, y: usize, width: usize, height: usize) -> bool {
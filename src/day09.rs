@@ -1,10 +1,13 @@
 use std::collections::HashSet;
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
+
+pub const NOTES: &str = "Steps the head one square at a time, with each knot following the one in \
+    front of it, and records where the tail has been.";
 
 pub fn solve() {
-    println!("Visited by 2 knots: {}", solve_impl::<2>());
-    println!("Visited by 10 knots: {}", solve_impl::<10>());
+    print_answer(1, format_args!("Visited by 2 knots: {}", solve_impl::<2>()));
+    print_answer(2, format_args!("Visited by 10 knots: {}", solve_impl::<10>()));
 }
 
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
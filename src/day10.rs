@@ -1,4 +1,7 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
+
+pub const NOTES: &str = "Simulates the CPU cycle by cycle, sampling signal strength and drawing \
+    the CRT as it goes.";
 
 pub fn solve() {
     let mut state = State::new();
@@ -14,15 +17,13 @@ pub fn solve() {
         }
     }
 
-    println!("Sum is {}", state.sum);
+    print_answer(1, format_args!("Sum is {}", state.sum));
     println!("Screen contents:");
     let screen_lines = state
         .screen
         .iter()
         .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect::<String>());
-    for line in screen_lines {
-        println!("{line}");
-    }
+    print_answer(2, screen_lines.collect::<Vec<_>>().join("\n"));
 }
 
 const SCREEN_WIDTH: usize = 40;
@@ -1,8 +1,20 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
+
+pub const NOTES: &str = "Simulates the rounds, keeping worry levels modulo the product of every \
+    monkey's divisor so they never overflow.";
 
 pub fn solve() {
-    println!("Amount of monkey business (with decay, 20 rounds): {}", solve_impl(20, 3),);
-    println!("Amount of monkey business (without decay, 10000 rounds): {}", solve_impl(10000, 1),);
+    print_answer(
+        1,
+        format_args!("Amount of monkey business (with decay, 20 rounds): {}", solve_impl(20, 3)),
+    );
+    print_answer(
+        2,
+        format_args!(
+            "Amount of monkey business (without decay, 10000 rounds): {}",
+            solve_impl(10000, 1)
+        ),
+    );
 }
 
 #[derive(Debug)]
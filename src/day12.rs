@@ -1,13 +1,83 @@
 use std::collections::{BTreeMap, HashMap};
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
+
+pub const NOTES: &str = "A* search over the height map, run from the start and then from every \
+    lowest point.";
+
+const LOWEST_ELEVATION: u8 = 97; // 'a'
+const HIGHEST_ELEVATION: u8 = 122; // 'z'
+const START_MARKER: u8 = 83; // 'S'
+const END_MARKER: u8 = 69; // 'E'
 
 pub fn solve() {
-    const LOWEST_ELEVATION: u8 = 97; // 'a'
-    const HIGHEST_ELEVATION: u8 = 122; // 'z'
-    const START_MARKER: u8 = 83; // 'S'
-    const END_MARKER: u8 = 69; // 'E'
+    let HeightMap { elevations, grid_width, start_idx, end_idx } = parse_height_map();
+
+    let all_lowest: Vec<usize> = elevations
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, b)| if *b == LOWEST_ELEVATION { Some(idx) } else { None })
+        .collect();
+
+    let grid: Vec<GridNode> = build_graph(&elevations, grid_width, end_idx);
+
+    let shortest_length =
+        find_shortest_path_length(&grid, start_idx, end_idx).expect("No possible path");
+    print_answer(1, format_args!("Shortest path length from start is {shortest_length}"));
+
+    let shortest_from_all_lowest = all_lowest
+        .iter()
+        .filter_map(|&start| find_shortest_path_length(&grid, start, end_idx))
+        .min()
+        .unwrap();
+    print_answer(
+        2,
+        format_args!("Shortest path length from any lowest point is {shortest_from_all_lowest}"),
+    );
+}
+
+// Prints the height map along with the graph built from it. Each square in the graph shows how many
+// neighbors can be stepped to from there
+pub fn show_grid() {
+    let HeightMap { elevations, grid_width, start_idx, end_idx } = parse_height_map();
+    let grid = build_graph(&elevations, grid_width, end_idx);
+    let coord = |idx: usize| (idx % grid_width, idx / grid_width);
+
+    println!("{} x {} grid", grid_width, elevations.len() / grid_width);
+    println!("Start at {:?}, end at {:?}", coord(start_idx), coord(end_idx));
+
+    println!("Elevations:");
+    for (y, row) in elevations.chunks(grid_width).enumerate() {
+        let line = row
+            .iter()
+            .enumerate()
+            .map(|(x, &b)| match y * grid_width + x {
+                idx if idx == start_idx => START_MARKER as char,
+                idx if idx == end_idx => END_MARKER as char,
+                _ => b as char,
+            })
+            .collect::<String>();
+        println!("{line}");
+    }
 
+    println!("Possible steps:");
+    for row in grid.chunks(grid_width) {
+        println!(
+            "{}",
+            row.iter().map(|node| node.edge_indices.len().to_string()).collect::<String>()
+        );
+    }
+}
+
+struct HeightMap {
+    elevations: Vec<u8>,
+    grid_width: usize,
+    start_idx: usize,
+    end_idx: usize,
+}
+
+// The start and end markers are replaced with their actual elevations
+fn parse_height_map() -> HeightMap {
     // Read the file into a 1D grid of bytes, noting the width for indexing later
     let mut grid_width: Option<usize> = None;
     let mut elevations: Vec<u8> = iterate_file_lines("day12input.txt")
@@ -29,24 +99,7 @@ pub fn solve() {
     elevations[start_idx] = LOWEST_ELEVATION;
     elevations[end_idx] = HIGHEST_ELEVATION;
 
-    let all_lowest: Vec<usize> = elevations
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, b)| if *b == LOWEST_ELEVATION { Some(idx) } else { None })
-        .collect();
-
-    let grid: Vec<GridNode> = build_graph(&elevations, grid_width, end_idx);
-
-    let shortest_length =
-        find_shortest_path_length(&grid, start_idx, end_idx).expect("No possible path");
-    println!("Shortest path length from start is {shortest_length}");
-
-    let shortest_from_all_lowest = all_lowest
-        .iter()
-        .filter_map(|&start| find_shortest_path_length(&grid, start, end_idx))
-        .min()
-        .unwrap();
-    println!("Shortest path length from any lowest point is {shortest_from_all_lowest}");
+    HeightMap { elevations, grid_width, start_idx, end_idx }
 }
 
 struct GridNode {
@@ -1,12 +1,18 @@
 use std::cmp::Ordering;
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer, Param};
+
+pub const NOTES: &str = "Recursive packet parser with an `Ord` implementation. Part 2 sorts the \
+    packets and finds where the dividers would go.";
+
+pub const PACKET: Param = Param {
+    name: "packet",
+    default: "1",
+    description: "Which packet the packet model shows, counting from 1",
+};
 
 pub fn solve() {
-    let mut all_packets: Vec<PacketValue> = iterate_file_lines("day13input.txt")
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_packet_value(&line))
-        .collect();
+    let mut all_packets = parse_packets();
 
     let mut sum = 0;
     for (i, [left, right]) in all_packets.iter().array_chunks().enumerate() {
@@ -15,7 +21,7 @@ pub fn solve() {
             sum += i + 1;
         }
     }
-    println!("Sum of in-order pair indices is {sum}");
+    print_answer(1, format_args!("Sum of in-order pair indices is {sum}"));
 
     all_packets.sort_unstable();
 
@@ -30,7 +36,31 @@ pub fn solve() {
     let second_divider_idx = all_packets.partition_point(|p| p < &second_divider) + 2;
 
     let decoder_key = first_divider_idx * second_divider_idx;
-    println!("Decoder key is {decoder_key}");
+    print_answer(2, format_args!("Decoder key is {decoder_key}"));
+}
+
+// Prints one packet's parsed structure, one list per line. Lists holding only integers are kept on a
+// single line
+pub fn show_packet() {
+    fn print_indented(packet: &PacketValue, depth: usize) {
+        let indent = "  ".repeat(depth);
+        match packet {
+            PacketValue::List(list) if list.iter().any(|p| matches!(p, PacketValue::List(_))) => {
+                println!("{indent}[");
+                list.iter().for_each(|p| print_indented(p, depth + 1));
+                println!("{indent}]");
+            }
+            packet => println!("{indent}{packet}"),
+        }
+    }
+
+    let packet_number: usize = PACKET.get();
+    let all_packets = parse_packets();
+    let packet = packet_number
+        .checked_sub(1)
+        .and_then(|idx| all_packets.get(idx))
+        .unwrap_or_else(|| panic!("There are only {} packets", all_packets.len()));
+    print_indented(packet, 0);
 }
 
 #[derive(Debug)]
@@ -39,6 +69,13 @@ enum PacketValue {
     List(Vec<PacketValue>),
 }
 
+fn parse_packets() -> Vec<PacketValue> {
+    iterate_file_lines("day13input.txt")
+        .filter(|line| !line.is_empty())
+        .map(|line| parse_packet_value(&line))
+        .collect()
+}
+
 fn parse_packet_value(packet_str: &str) -> PacketValue {
     match packet_str {
         "[]" => PacketValue::List(vec![]),
@@ -84,6 +121,18 @@ impl Ord for PacketValue {
     }
 }
 
+impl std::fmt::Display for PacketValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PacketValue::Int(n) => write!(f, "{n}"),
+            PacketValue::List(list) => {
+                let items = list.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+                write!(f, "[{}]", items.join(","))
+            }
+        }
+    }
+}
+
 // All unfortunate boilerplate to satisfy the requirements of Ord
 impl Eq for PacketValue {}
 impl PartialOrd for PacketValue {
@@ -1,6 +1,6 @@
 use std::mem::swap;
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum TileType {
@@ -24,6 +24,9 @@ struct BoundingBox {
 
 const SAND_START: Coord = Coord { x: 500, y: 0 };
 
+pub const NOTES: &str = "Sand simulation on a grid sized to the rocks' bounding box, widened and \
+    given a floor for part 2.";
+
 pub fn solve() {
     let paths = parse_paths();
     let bbox = calc_bounding_box(&paths);
@@ -32,7 +35,10 @@ pub fn solve() {
     {
         let mut grid = construct_grid(&paths, &bbox);
         let rest_before_abyss = simulate_sand(&mut grid, &bbox);
-        println!("Units of sand that came to rest before the abyss: {rest_before_abyss}");
+        print_answer(
+            1,
+            format_args!("Units of sand that came to rest before the abyss: {rest_before_abyss}"),
+        );
     }
 
     // Part 2
@@ -63,7 +69,12 @@ pub fn solve() {
 
         let mut grid = construct_grid(&paths, &bbox);
         let rest_before_abyss = simulate_sand(&mut grid, &bbox);
-        println!("Units of sand that came to rest before source block: {rest_before_abyss}");
+        print_answer(
+            2,
+            format_args!(
+                "Units of sand that came to rest before source block: {rest_before_abyss}"
+            ),
+        );
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer, Param};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 struct Coord {
@@ -12,6 +12,21 @@ struct Sensor {
     manhattan: i32,
 }
 
+pub const NOTES: &str = "Merges sensor coverage ranges for a row. Part 2 scans every row for a \
+    one-square gap.";
+
+pub const ROW: Param = Param {
+    name: "row",
+    default: "2000000",
+    description: "Row to count beacon-free positions on (10 for the example)",
+};
+
+pub const SEARCH_MAX: Param = Param {
+    name: "search_max",
+    default: "4000000",
+    description: "Largest X and Y coordinate of the distress beacon (20 for the example)",
+};
+
 pub fn solve() {
     let mut sensors = Vec::new();
     let mut beacons = Vec::new();
@@ -37,13 +52,16 @@ pub fn solve() {
         sensors.push(Sensor { pos: sensor_coord, manhattan });
     }
 
-    let part1_row = 2000000; // Test: 10, Actual: 2000000
+    let part1_row: i32 = ROW.get();
     let num_positions = solve_part1(part1_row, &sensors, &beacons);
-    println!("{num_positions} positions cannot contain a beacon on row {part1_row}");
+    print_answer(
+        1,
+        format_args!("{num_positions} positions cannot contain a beacon on row {part1_row}"),
+    );
 
-    let part2_range = 0..4000001; // Test: 21, Actual: 4000001
+    let part2_range = 0..(SEARCH_MAX.get::<i32>() + 1);
     let tuning_freq = solve_part2(&sensors, part2_range).expect("No position found");
-    println!("Tuning frequency: {tuning_freq}");
+    print_answer(2, format_args!("Tuning frequency: {tuning_freq}"));
 }
 
 fn parse_coord(s: &str) -> Coord {
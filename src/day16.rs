@@ -1,6 +1,6 @@
 use std::time::Instant;
 
-use crate::helpers::iterate_file_lines;
+use crate::helpers::{iterate_file_lines, print_answer};
 
 struct UnresolvedValve {
     valve_name: String,
@@ -14,13 +14,17 @@ struct Valve {
     tunnels: Vec<usize>,
 }
 
+pub const NOTES: &str = "Exhaustive depth-first search over moves and valve openings. Only part 1 \
+    is solved.";
+
 pub fn solve() {
     let (valves, start_idx) = parse_valves();
     let start_time = Instant::now();
     let max_pressure = solve_part1(&valves, start_idx);
     let time_taken = Instant::elapsed(&start_time);
-    println!("Max releasable pressure is {max_pressure}");
-    println!("Took {} seconds", time_taken.as_secs_f32());
+    print_answer(1, format_args!("Max releasable pressure is {max_pressure}"));
+    // Kept out of stdout so it isn't mistaken for an answer
+    eprintln!("Took {} seconds", time_taken.as_secs_f32());
 }
 
 fn solve_part1(valves: &[Valve], start_idx: usize) -> usize {
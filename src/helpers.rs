@@ -1,11 +1,144 @@
 use std::{
+    fmt::Display,
     fs::File,
-    io::{BufRead, BufReader},
-    path::Path,
+    io::{BufRead, BufReader, Cursor, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
 };
 
+use crate::runner::{label_line, LABEL_PARTS_VAR};
+
+const INPUT_DIR: &str = "./input";
+
+// Set from the command line, so that a solver can be pointed at some other input without needing
+// to know about it
+static INPUT_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+static PARAM_OVERRIDES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+// Stdin can only be read once, but most days go through their input more than once (often once per
+// part). So it's read in full the first time and replayed from then on
+static STDIN_CONTENTS: Mutex<Option<Arc<[u8]>>> = Mutex::new(None);
+
+// A tunable value a day declares for itself, which can be overridden with `--param name=value`
+pub struct Param {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+impl Param {
+    pub fn get<T: FromStr>(&self) -> T {
+        let overrides = PARAM_OVERRIDES.lock().unwrap();
+        let value = overrides
+            .iter()
+            .rev()
+            .find(|(name, _)| name == self.name)
+            .map_or(self.default, |(_, value)| value.as_str());
+        value.parse().unwrap_or_else(|_| panic!("Invalid value for parameter {}", self.name))
+    }
+}
+
+pub fn set_input_override(path: PathBuf) {
+    *INPUT_OVERRIDE.lock().unwrap() = Some(path);
+}
+
+pub fn set_param_override(name: &str, value: &str) {
+    PARAM_OVERRIDES.lock().unwrap().push((name.to_string(), value.to_string()));
+}
+
+fn resolve_input_path(file_path: &str) -> PathBuf {
+    match &*INPUT_OVERRIDE.lock().unwrap() {
+        Some(path) => path.clone(),
+        None => Path::new(INPUT_DIR).join(file_path),
+    }
+}
+
+fn stdin_contents() -> Arc<[u8]> {
+    let mut contents = STDIN_CONTENTS.lock().unwrap();
+    contents
+        .get_or_insert_with(|| {
+            let mut bytes = Vec::new();
+            std::io::stdin().read_to_end(&mut bytes).expect("Could not read stdin");
+            bytes.into()
+        })
+        .clone()
+}
+
+// An input path of "-" reads from stdin instead
+pub fn open_input(file_path: &str) -> Box<dyn BufRead> {
+    let path = resolve_input_path(file_path);
+    if path.as_os_str() == "-" {
+        Box::new(Cursor::new(stdin_contents()))
+    } else {
+        Box::new(BufReader::new(File::open(path).expect("Could not open file")))
+    }
+}
+
+// Like `open_input`, but stdin is read as it comes rather than held in memory. It can only be
+// read this way once
+pub fn stream_input(file_path: &str) -> Box<dyn BufRead> {
+    let path = resolve_input_path(file_path);
+    if path.as_os_str() == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        open_input(file_path)
+    }
+}
+
 pub fn iterate_file_lines(file_path: &str) -> impl Iterator<Item = String> {
-    let file = File::open(Path::new("./input").join(file_path)).expect("Could not open file");
-    let buf_reader = BufReader::new(file);
-    buf_reader.lines().map(|line| line.expect("Error reading next line"))
+    open_input(file_path).lines().map(|line| line.expect("Error reading next line"))
+}
+
+pub fn read_input_file(file_path: &str) -> String {
+    let mut contents = String::new();
+    open_input(file_path).read_to_string(&mut contents).expect("Could not read input file");
+    contents
+}
+
+// For inputs that aren't necessarily text
+pub fn read_input_bytes(file_path: &str) -> Vec<u8> {
+    let mut contents = Vec::new();
+    open_input(file_path).read_to_end(&mut contents).expect("Could not read input file");
+    contents
+}
+
+// Shows the prompt and reads the next command, split into words, skipping blank lines. Returns None
+// once the user quits or the input ends
+pub fn read_command(prompt: &str) -> Option<Vec<String>> {
+    loop {
+        print!("{prompt}");
+        std::io::stdout().flush().expect("Could not write prompt");
+
+        let mut line = String::new();
+        if std::io::stdin().read_line(&mut line).expect("Could not read command") == 0 {
+            // End of input
+            println!();
+            return None;
+        }
+
+        let words: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+        match words.first().map(String::as_str) {
+            None => continue,
+            Some("quit" | "exit") => return None,
+            Some(_) => return Some(words),
+        }
+    }
+}
+
+pub fn input_path(day_number: usize) -> PathBuf {
+    Path::new(INPUT_DIR).join(format!("day{day_number:02}input.txt"))
+}
+
+// Prints the answer to a part, which may run over several lines. Under the runner, each line is
+// marked with the part so that one part can be picked out of the output
+pub fn print_answer(part: usize, answer: impl Display) {
+    let label = std::env::var_os(LABEL_PARTS_VAR).is_some();
+    for line in answer.to_string().lines() {
+        if label {
+            println!("{}", label_line(part, line));
+        } else {
+            println!("{line}");
+        }
+    }
 }
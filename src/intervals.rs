@@ -0,0 +1,216 @@
+use std::fmt;
+
+// A range of sections, including both ends
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Range {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Range {
+    pub fn len(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+// A set of sections, kept as ranges that are sorted, and neither overlap nor touch. Keeping them
+// that way means each set has exactly one representation, so equality is just comparing ranges
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IntervalSet {
+    ranges: Vec<Range>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Any ranges at all, overlapping or in any order
+    pub fn from_ranges(ranges: impl IntoIterator<Item = Range>) -> Self {
+        let mut ranges: Vec<Range> = ranges.into_iter().collect();
+        ranges.sort_unstable_by_key(|range| range.start);
+        Self { ranges: Self::normalise(ranges) }
+    }
+
+    // Merges ranges that overlap or touch. They have to be sorted by their starts already
+    fn normalise(sorted: Vec<Range>) -> Vec<Range> {
+        let mut merged: Vec<Range> = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end + 1 => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    // Number of sections, not ranges
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(Range::len).sum()
+    }
+
+    pub fn contains(&self, section: usize) -> bool {
+        self.contains_range(Range { start: section, end: section })
+    }
+
+    pub fn contains_range(&self, range: Range) -> bool {
+        // The only range that could hold it is the last one starting at or before it
+        let idx = self.ranges.partition_point(|r| r.start <= range.start);
+        idx > 0 && self.ranges[idx - 1].end >= range.end
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        Self::from_ranges(self.ranges.iter().chain(&other.ranges).copied())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a.start.max(b.start), a.end.min(b.end));
+            if start <= end {
+                result.push(Range { start, end });
+            }
+            // Whichever finishes first can't overlap anything else
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges: result }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut result = Vec::new();
+        let mut others = other.ranges.iter().peekable();
+
+        for range in &self.ranges {
+            let mut start = range.start;
+            // Skip what finished before this range, then cut out whatever lies inside it
+            while others.next_if(|o| o.end < start).is_some() {}
+            while let Some(o) = others.peek().map(|&o| *o).filter(|o| o.start <= range.end) {
+                if o.start > start {
+                    result.push(Range { start, end: o.start - 1 });
+                }
+                if o.end >= range.end {
+                    start = range.end + 1;
+                    break;
+                }
+                start = o.end + 1;
+                others.next();
+            }
+            if start <= range.end {
+                result.push(Range { start, end: range.end });
+            }
+        }
+
+        Self { ranges: result }
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{range}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(usize, usize)]) -> IntervalSet {
+        IntervalSet::from_ranges(ranges.iter().map(|&(start, end)| Range { start, end }))
+    }
+
+    fn pairs(set: &IntervalSet) -> Vec<(usize, usize)> {
+        set.ranges().iter().map(|range| (range.start, range.end)).collect()
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent_ranges() {
+        assert_eq!(pairs(&set(&[(6, 8), (1, 3), (2, 4)])), vec![(1, 4), (6, 8)]);
+        assert_eq!(pairs(&set(&[(1, 3), (4, 6)])), vec![(1, 6)]);
+        assert_eq!(pairs(&set(&[(1, 3), (5, 6)])), vec![(1, 3), (5, 6)]);
+        assert_eq!(pairs(&set(&[(2, 9), (3, 4)])), vec![(2, 9)]);
+    }
+
+    #[test]
+    fn single_sections() {
+        let single = set(&[(0, 0), (7, 7), (8, 8)]);
+        assert_eq!(pairs(&single), vec![(0, 0), (7, 8)]);
+        assert_eq!(single.len(), 3);
+        assert!(single.contains(0) && single.contains(8));
+        assert!(!single.contains(1) && !single.contains(9));
+        assert_eq!(single.to_string(), "0, 7-8");
+    }
+
+    #[test]
+    fn empty_set() {
+        let empty = IntervalSet::new();
+        let other = set(&[(1, 5)]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert!(!empty.contains(0));
+        assert_eq!(empty.union(&other), other);
+        assert!(empty.intersection(&other).is_empty());
+        assert!(empty.difference(&other).is_empty());
+        assert_eq!(other.difference(&empty), other);
+        assert_eq!(empty.to_string(), "");
+    }
+
+    #[test]
+    fn union() {
+        let a = set(&[(1, 3), (10, 12)]);
+        let b = set(&[(4, 5), (11, 20)]);
+        assert_eq!(pairs(&a.union(&b)), vec![(1, 5), (10, 20)]);
+    }
+
+    #[test]
+    fn intersection() {
+        let a = set(&[(1, 5), (8, 12)]);
+        let b = set(&[(3, 9), (12, 15)]);
+        assert_eq!(pairs(&a.intersection(&b)), vec![(3, 5), (8, 9), (12, 12)]);
+        assert!(set(&[(1, 3)]).intersection(&set(&[(4, 6)])).is_empty());
+    }
+
+    #[test]
+    fn difference() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(0, 2), (5, 6), (10, 21), (30, 30)]);
+        assert_eq!(pairs(&a.difference(&b)), vec![(3, 4), (7, 9), (22, 29)]);
+        assert!(a.difference(&a).is_empty());
+    }
+
+    #[test]
+    fn contains_range() {
+        let a = set(&[(1, 5), (7, 9)]);
+        assert!(a.contains_range(Range { start: 2, end: 5 }));
+        assert!(!a.contains_range(Range { start: 4, end: 7 }));
+        assert!(!a.contains_range(Range { start: 0, end: 1 }));
+    }
+}
@@ -5,6 +5,7 @@
 #![feature(let_chains)]
 #![allow(dead_code)]
 
+mod cache;
 mod day01;
 mod day02;
 mod day03;
@@ -22,35 +23,248 @@ mod day14;
 mod day15;
 mod day16;
 mod helpers;
+mod intervals;
+mod repl;
+mod report;
+mod runner;
+mod serve;
+mod watch;
 
-const DAY_SOLVERS: &[fn()] = &[
-    day01::solve,
-    day02::solve,
-    day03::solve,
-    day04::solve,
-    day05::solve,
-    day06::solve,
-    day07::solve,
-    day08::solve,
-    day09::solve,
-    day10::solve,
-    day11::solve,
-    day12::solve,
-    day13::solve,
-    day14::solve,
-    day15::solve,
-    day16::solve,
+use helpers::Param;
+
+pub struct Day {
+    pub title: &'static str,
+    // The first implementation is the one used unless another is asked for
+    pub implementations: &'static [Implementation],
+    pub params: &'static [Param],
+    pub notes: &'static str,
+    // Views of the day's intermediate data, for poking around with
+    pub models: &'static [Model],
+}
+
+pub struct Implementation {
+    pub name: &'static str,
+    pub solve: fn(),
+}
+
+pub struct Model {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub show: fn(),
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        title: "Calorie Counting",
+        implementations: &[
+            Implementation { name: "standard", solve: day01::solve },
+            Implementation { name: "ranked", solve: day01::solve_ranked },
+        ],
+        params: &[day01::TOP_K, day01::HISTOGRAM_BUCKETS],
+        notes: day01::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Rock Paper Scissors",
+        implementations: &[
+            Implementation { name: "standard", solve: day02::solve },
+            Implementation { name: "decode", solve: day02::solve_decode },
+            Implementation { name: "opponent", solve: day02::solve_opponent },
+        ],
+        params: &[day02::CHOICES, day02::OUTCOME_SCORES],
+        notes: day02::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Rucksack Reorganization",
+        implementations: &[
+            Implementation { name: "standard", solve: day03::solve },
+            Implementation { name: "shuffled", solve: day03::solve_shuffled },
+        ],
+        params: &[day03::GROUP_SIZE],
+        notes: day03::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Camp Cleanup",
+        implementations: &[
+            Implementation { name: "standard", solve: day04::solve },
+            Implementation { name: "coverage", solve: day04::solve_coverage },
+            Implementation { name: "reassign", solve: day04::solve_reassign },
+        ],
+        params: &[],
+        notes: day04::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Supply Stacks",
+        implementations: &[
+            Implementation { name: "standard", solve: day05::solve },
+            Implementation { name: "short", solve: day05::solve_short },
+            Implementation { name: "crane", solve: day05::solve_crane },
+            Implementation { name: "reverse", solve: day05::solve_reverse },
+        ],
+        params: &[
+            day05::CAPACITY,
+            day05::FPS,
+            day05::HIGHLIGHT,
+            day05::FRAMES_FILE,
+            day05::FINAL_LAYOUT,
+            day05::MESSAGE,
+        ],
+        notes: day05::NOTES,
+        models: &[Model {
+            name: "moves",
+            description: "The stacks after every move, played back in the terminal",
+            show: day05::show_moves,
+        }],
+    },
+    Day {
+        title: "Tuning Trouble",
+        implementations: &[
+            Implementation { name: "standard", solve: day06::solve },
+            Implementation { name: "short", solve: day06::solve_short },
+            Implementation { name: "stream", solve: day06::solve_stream },
+            Implementation { name: "skip_ahead", solve: day06::solve_skip_ahead },
+            Implementation { name: "bench", solve: day06::solve_bench },
+            Implementation { name: "noisy", solve: day06::solve_noisy },
+        ],
+        params: &[day06::WINDOWS, day06::LIST_MARKERS, day06::BENCH_TIME, day06::MAX_REPEATS],
+        notes: day06::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "No Space Left On Device",
+        implementations: &[
+            Implementation { name: "standard", solve: day07::solve },
+            Implementation { name: "query", solve: day07::solve_query },
+            Implementation { name: "check", solve: day07::solve_check },
+            Implementation { name: "plan", solve: day07::solve_plan },
+        ],
+        params: &[day07::LARGEST, day07::GLOB, day07::AT_LEAST, day07::AT_MOST, day07::WITH_FILES],
+        notes: day07::NOTES,
+        models: &[Model {
+            name: "tree",
+            description: "Directory tree with files and sizes, like tree -h",
+            show: day07::show_tree,
+        }],
+    },
+    Day {
+        title: "Treetop Tree House",
+        implementations: &[Implementation { name: "standard", solve: day08::solve }],
+        params: &[],
+        notes: day08::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Rope Bridge",
+        implementations: &[Implementation { name: "standard", solve: day09::solve }],
+        params: &[],
+        notes: day09::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Cathode-Ray Tube",
+        implementations: &[Implementation { name: "standard", solve: day10::solve }],
+        params: &[],
+        notes: day10::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Monkey in the Middle",
+        implementations: &[Implementation { name: "standard", solve: day11::solve }],
+        params: &[],
+        notes: day11::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Hill Climbing Algorithm",
+        implementations: &[Implementation { name: "standard", solve: day12::solve }],
+        params: &[],
+        notes: day12::NOTES,
+        models: &[Model {
+            name: "grid",
+            description: "Height map and the graph built from it",
+            show: day12::show_grid,
+        }],
+    },
+    Day {
+        title: "Distress Signal",
+        implementations: &[Implementation { name: "standard", solve: day13::solve }],
+        params: &[day13::PACKET],
+        notes: day13::NOTES,
+        models: &[Model {
+            name: "packet",
+            description: "Parsed structure of one packet",
+            show: day13::show_packet,
+        }],
+    },
+    Day {
+        title: "Regolith Reservoir",
+        implementations: &[Implementation { name: "standard", solve: day14::solve }],
+        params: &[],
+        notes: day14::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Beacon Exclusion Zone",
+        implementations: &[Implementation { name: "standard", solve: day15::solve }],
+        params: &[day15::ROW, day15::SEARCH_MAX],
+        notes: day15::NOTES,
+        models: &[],
+    },
+    Day {
+        title: "Proboscidea Volcanium",
+        implementations: &[Implementation { name: "standard", solve: day16::solve }],
+        params: &[],
+        notes: day16::NOTES,
+        models: &[],
+    },
 ];
 
+const DEFAULT_PORT: u16 = 8022;
+
 fn print_usage_and_exit(program_name: &str) -> ! {
     let file_name = std::path::Path::new(program_name)
         .file_name()
         .and_then(|name| name.to_str())
         .unwrap_or("program_name");
 
-    eprintln!("USAGE: {file_name} [day_number (1-{})]", DAY_SOLVERS.len());
+    eprintln!("USAGE: {file_name} [day_number (1-{})] [options]", DAYS.len());
     eprintln!("If omitted, the day number defaults to the latest day.");
     eprintln!("EXAMPLE: \"{file_name} 2\" solves Day 2.");
+    eprintln!();
+    eprintln!("OPTIONS:");
+    eprintln!("  --input PATH         Read the puzzle input from PATH instead of ./input");
+    eprintln!("  --param NAME=VALUE   Override one of the day's parameters");
+    eprintln!("  --impl NAME          Use another of the day's implementations");
+    eprintln!("  --model NAME         Print one of the day's models instead of solving");
+    eprintln!();
+    eprintln!("USAGE: {file_name} --all [--no-cache]");
+    eprintln!("Solves every day, reusing cached answers where nothing has changed.");
+    eprintln!();
+    eprintln!("USAGE: {file_name} report [path] [--no-cache]");
+    eprintln!(
+        "Solves every day and writes a Markdown table of the results (default {}).",
+        report::DEFAULT_REPORT_PATH
+    );
+    eprintln!();
+    eprintln!("USAGE: {file_name} repl");
+    eprintln!("Starts an interactive prompt for loading inputs and running days.");
+    eprintln!();
+    eprintln!("USAGE: {file_name} shell [path]");
+    eprintln!(
+        "Starts a prompt for looking around Day 7's filesystem, rebuilt from the log at path."
+    );
+    eprintln!();
+    eprintln!("USAGE: {file_name} cache clear");
+    eprintln!("Deletes all cached answers.");
+    eprintln!();
+    eprintln!("USAGE: {file_name} watch [day_number] [options]");
+    eprintln!("Re-solves the day whenever its input file changes, showing what changed.");
+    eprintln!();
+    eprintln!("USAGE: {file_name} serve [--port PORT]");
+    eprintln!("Serves the solvers over HTTP on localhost (port {DEFAULT_PORT} by default).");
     std::process::exit(1);
 }
 
@@ -59,25 +273,157 @@ fn fatal_error(s: &str) -> ! {
     std::process::exit(1);
 }
 
+fn parse_day_number(s: &str) -> usize {
+    let n = s.parse::<isize>().unwrap_or_else(|_| fatal_error("Expected a day number"));
+    if n < 1 || (n as usize) > DAYS.len() {
+        fatal_error("Invalid day number")
+    }
+    n as usize
+}
+
+// Splits off the leading day number, if there is one
+fn split_day_number(args: &[String]) -> (usize, &[String]) {
+    match args {
+        [s, rest @ ..] if !s.starts_with("--") => (parse_day_number(s), rest),
+        _ => (DAYS.len(), args),
+    }
+}
+
+// Returns what to run: the implementation to solve the day with, or a model to show
+fn apply_options(program_name: &str, day_number: usize, options: &[String]) -> fn() {
+    let day = &DAYS[day_number - 1];
+    let mut to_run = day.implementations[0].solve;
+
+    let mut iter = options.iter();
+    while let Some(option) = iter.next() {
+        let value = iter.next().unwrap_or_else(|| print_usage_and_exit(program_name));
+        match option.as_str() {
+            "--input" => helpers::set_input_override(value.into()),
+            "--param" => {
+                let (name, value) = value
+                    .split_once('=')
+                    .unwrap_or_else(|| fatal_error("Expected a parameter as NAME=VALUE"));
+                if !day.params.iter().any(|param| param.name == name) {
+                    fatal_error(&format!("Day {day_number} has no parameter named {name}"));
+                }
+                helpers::set_param_override(name, value);
+            }
+            "--impl" => {
+                to_run = day
+                    .implementations
+                    .iter()
+                    .find(|imp| imp.name == value)
+                    .unwrap_or_else(|| {
+                        fatal_error(&format!("Day {day_number} has no implementation {value}"))
+                    })
+                    .solve;
+            }
+            "--model" => {
+                to_run = day
+                    .models
+                    .iter()
+                    .find(|model| model.name == value)
+                    .unwrap_or_else(|| {
+                        fatal_error(&format!("Day {day_number} has no model {value}"))
+                    })
+                    .show;
+            }
+            _ => print_usage_and_exit(program_name),
+        }
+    }
+
+    to_run
+}
+
+fn solve_all(use_cache: bool) {
+    let cache = use_cache.then(cache::Cache::open);
+
+    for (i, day) in DAYS.iter().enumerate() {
+        let day_number = i + 1;
+        println!("--- Day {day_number}: {} ---", day.title);
+
+        let (output, from_cache) = cache::run_day_cached(cache.as_ref(), day_number)
+            .unwrap_or_else(|e| fatal_error(&format!("Could not run solver: {e}")));
+
+        for line in &output.lines {
+            println!("{line}");
+        }
+        if !output.success {
+            print!("{}", output.stderr);
+        }
+
+        if from_cache {
+            println!("(cached, originally took {:.2?})", output.elapsed);
+        } else {
+            println!("(took {:.2?})", output.elapsed);
+        }
+    }
+}
+
 fn main() {
     let args: Vec<_> = std::env::args().collect();
 
-    let day_number: usize = match args.as_slice() {
-        [name, _, _, ..] => print_usage_and_exit(name),
-        [name, s] => {
-            if s == "--usage" || s == "--help" {
-                print_usage_and_exit(name);
+    match args.as_slice() {
+        [name, rest @ ..] if rest.iter().any(|s| s == "--usage" || s == "--help") => {
+            print_usage_and_exit(name)
+        }
+        [name, flag, rest @ ..] if flag == "--all" => match rest {
+            [] => solve_all(true),
+            [flag] if flag == "--no-cache" => solve_all(false),
+            _ => print_usage_and_exit(name),
+        },
+        [name, cmd, rest @ ..] if cmd == "report" => {
+            let (use_cache, rest) = match rest {
+                [rest @ .., flag] if flag == "--no-cache" => (false, rest),
+                _ => (true, rest),
+            };
+            match rest {
+                [] => report::write_report(report::DEFAULT_REPORT_PATH, use_cache),
+                [path] => report::write_report(path, use_cache),
+                _ => print_usage_and_exit(name),
             }
-
-            let n = s.parse::<isize>().unwrap_or_else(|_| fatal_error("Expected a day number"));
-            if n < 1 || (n as usize) > DAY_SOLVERS.len() {
-                fatal_error("Invalid day number")
+        }
+        [name, cmd, rest @ ..] if cmd == "repl" => match rest {
+            [] => repl::repl(),
+            _ => print_usage_and_exit(name),
+        },
+        [name, cmd, rest @ ..] if cmd == "shell" => match rest {
+            [] => day07::shell(),
+            [path] if path == "-" => {
+                fatal_error("The shell reads commands from stdin, so the log has to be a file")
             }
-            n as usize
+            [path] => {
+                helpers::set_input_override(path.into());
+                day07::shell()
+            }
+            _ => print_usage_and_exit(name),
+        },
+        [name, cmd, rest @ ..] if cmd == "cache" => match rest {
+            [sub] if sub == "clear" => {
+                cache::clear().unwrap_or_else(|e| fatal_error(&format!("Could not clear: {e}")));
+                println!("Cache cleared");
+            }
+            _ => print_usage_and_exit(name),
+        },
+        [name, cmd, rest @ ..] if cmd == "watch" => {
+            let (day_number, options) = split_day_number(rest);
+            apply_options(name, day_number, options);
+            watch::watch(day_number, options)
         }
-        _ => DAY_SOLVERS.len(),
-    };
+        [name, cmd, rest @ ..] if cmd == "serve" => match rest {
+            [] => serve::serve(DEFAULT_PORT),
+            [flag, port] if flag == "--port" => {
+                serve::serve(port.parse().unwrap_or_else(|_| fatal_error("Invalid port number")))
+            }
+            _ => print_usage_and_exit(name),
+        },
+        [name, rest @ ..] => {
+            let (day_number, options) = split_day_number(rest);
+            let to_run = apply_options(name, day_number, options);
 
-    println!("--- Solving Day {day_number} ---");
-    DAY_SOLVERS[day_number - 1]();
+            println!("--- Solving Day {day_number} ---");
+            to_run();
+        }
+        [] => print_usage_and_exit("program_name"),
+    }
 }
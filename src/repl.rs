@@ -0,0 +1,159 @@
+use crate::{helpers::read_command, runner::run_day, DAYS};
+
+const HELP: &str = "\
+Commands:
+  day N            Switch to day N, resetting everything below
+  days             List the days
+  load [PATH]      Read the input from PATH, or the day's usual input if omitted
+  set NAME VALUE   Set one of the day's parameters
+  unset NAME       Put a parameter back to its default
+  params           List the day's parameters and their current values
+  impl [NAME]      Switch implementation, or list them if no name is given
+  run [PART]       Solve the day, or show only the given part's answer
+  show [MODEL]     Print one of the day's models, or list them if no name is given
+  help             Show this message
+  quit             Leave";
+
+// Everything here maps onto the command-line options, and each run happens in a child process just
+// like a normal invocation. So a solver that panics doesn't end the session
+struct Session {
+    day_number: usize,
+    input: Option<String>,
+    params: Vec<(&'static str, String)>,
+    implementation: &'static str,
+}
+
+impl Session {
+    fn new(day_number: usize) -> Self {
+        let day = &DAYS[day_number - 1];
+        Self {
+            day_number,
+            input: None,
+            params: Vec::new(),
+            implementation: day.implementations[0].name,
+        }
+    }
+
+    fn solver_args(&self) -> Vec<String> {
+        let mut args = vec!["--impl".to_string(), self.implementation.to_string()];
+        if let Some(input) = &self.input {
+            args.extend(["--input".to_string(), input.clone()]);
+        }
+        for (name, value) in &self.params {
+            args.extend(["--param".to_string(), format!("{name}={value}")]);
+        }
+        args
+    }
+
+    // Prints the solver's output, or only the lines it marked as answering the chosen part
+    fn run(&self, args: &[String], part: Option<usize>) {
+        let output = match run_day(self.day_number, args) {
+            Ok(output) => output,
+            Err(e) => return println!("Could not run solver: {e}"),
+        };
+
+        match part {
+            None => output.lines.iter().for_each(|line| println!("{line}")),
+            Some(n) => {
+                let lines = output.part_lines(n);
+                if lines.is_empty() && output.success {
+                    println!("The {} implementation doesn't give a part {n}", self.implementation);
+                }
+                lines.iter().for_each(|line| println!("{line}"));
+            }
+        }
+        if !output.success {
+            print!("{}", output.stderr);
+        }
+        println!("(took {:.2?})", output.elapsed);
+    }
+
+    fn execute(&mut self, command: &str, args: &[&str]) {
+        let day = &DAYS[self.day_number - 1];
+
+        match (command, args) {
+            ("help", []) => println!("{HELP}"),
+            ("day", [n]) => match n.parse::<usize>() {
+                Ok(n) if (1..=DAYS.len()).contains(&n) => *self = Session::new(n),
+                _ => println!("Expected a day from 1 to {}", DAYS.len()),
+            },
+            ("days", []) => {
+                for (i, day) in DAYS.iter().enumerate() {
+                    println!("{:>2}: {}", i + 1, day.title);
+                }
+            }
+            ("load", []) => self.input = None,
+            ("load", [path]) => {
+                if std::path::Path::new(path).is_file() {
+                    self.input = Some(path.to_string());
+                } else {
+                    println!("No such file {path}");
+                }
+            }
+            ("set", [name, value]) => match day.params.iter().find(|param| param.name == *name) {
+                Some(param) => {
+                    self.params.retain(|(set_name, _)| set_name != name);
+                    self.params.push((param.name, value.to_string()));
+                }
+                None => println!("Day {} has no parameter named {name}", self.day_number),
+            },
+            ("unset", [name]) => self.params.retain(|(set_name, _)| set_name != name),
+            ("params", []) => {
+                if day.params.is_empty() {
+                    println!("Day {} has no parameters", self.day_number);
+                }
+                for param in day.params {
+                    let value = self
+                        .params
+                        .iter()
+                        .find(|(name, _)| *name == param.name)
+                        .map_or(param.default, |(_, value)| value.as_str());
+                    println!("{} = {value} ({})", param.name, param.description);
+                }
+            }
+            ("impl", []) => {
+                for implementation in day.implementations {
+                    let marker = if implementation.name == self.implementation { "*" } else { " " };
+                    println!("{marker} {}", implementation.name);
+                }
+            }
+            ("impl", [name]) => match day.implementations.iter().find(|imp| imp.name == *name) {
+                Some(implementation) => self.implementation = implementation.name,
+                None => println!("Day {} has no implementation {name}", self.day_number),
+            },
+            ("run", []) => self.run(&self.solver_args(), None),
+            ("run", [part]) => match part.parse::<usize>() {
+                Ok(n) if n > 0 => self.run(&self.solver_args(), Some(n)),
+                _ => println!("Expected a part number"),
+            },
+            ("show", []) => {
+                if day.models.is_empty() {
+                    println!("Day {} has no models", self.day_number);
+                }
+                for model in day.models {
+                    println!("{}: {}", model.name, model.description);
+                }
+            }
+            ("show", [name]) => {
+                if day.models.iter().any(|model| model.name == *name) {
+                    let mut args = self.solver_args();
+                    args.extend(["--model".to_string(), name.to_string()]);
+                    self.run(&args, None);
+                } else {
+                    println!("Day {} has no model {name}", self.day_number);
+                }
+            }
+            _ => println!("Unknown command, try \"help\""),
+        }
+    }
+}
+
+pub fn repl() {
+    let mut session = Session::new(DAYS.len());
+    println!("Type \"help\" for a list of commands");
+
+    while let Some(words) = read_command(&format!("day {}> ", session.day_number)) {
+        let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+        session.execute(&words[0], &args);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt::Write as _;
+
+use crate::{
+    cache::{run_day_cached, Cache},
+    fatal_error, DAYS,
+};
+
+pub const DEFAULT_REPORT_PATH: &str = "RESULTS.md";
+
+pub fn write_report(path: &str, use_cache: bool) {
+    let cache = use_cache.then(Cache::open);
+
+    let mut table = String::from(
+        "| Day | Title | Part 1 | Part 2 | Time | Implementation | Notes |\n\
+         |----:|-------|--------|--------|-----:|----------------|-------|\n",
+    );
+    let mut full_outputs = String::new();
+
+    for (i, day) in DAYS.iter().enumerate() {
+        let day_number = i + 1;
+        eprintln!("Solving Day {day_number}...");
+
+        let (output, _) = run_day_cached(cache.as_ref(), day_number)
+            .unwrap_or_else(|e| fatal_error(&format!("Could not run solver: {e}")));
+
+        // Each answer is normally a line of its own. Anything longer (like Day 10's screen) won't
+        // fit in a table cell, so it gets its own section instead
+        let mut cell = |part: usize| match output.part_lines(part).as_slice() {
+            [] => String::new(),
+            [line] => table_cell(line),
+            lines => {
+                let heading = format!("Day {day_number} part {part}: {}", day.title);
+                writeln!(full_outputs, "\n## {heading}\n\n```").unwrap();
+                lines.iter().for_each(|line| writeln!(full_outputs, "{line}").unwrap());
+                full_outputs.push_str("```\n");
+                format!("See [below](#{})", anchor(&heading))
+            }
+        };
+        let (part1, part2) = if !output.success {
+            ("*Failed*".to_string(), "*Failed*".to_string())
+        } else {
+            match (cell(1), cell(2)) {
+                (part1, part2) if part1.is_empty() && part2.is_empty() => {
+                    ("*No output*".to_string(), String::new())
+                }
+                parts => parts,
+            }
+        };
+
+        writeln!(
+            table,
+            "| {day_number} | {} | {part1} | {part2} | {:.2?} | {} | {} |",
+            table_cell(day.title),
+            output.elapsed,
+            day.implementations[0].name,
+            table_cell(day.notes),
+        )
+        .unwrap();
+    }
+
+    let report = format!(
+        "# Results\n\n\
+         Generated by `cargo run --release -- report`. Times are from the run that produced each \
+         answer, which may have been an earlier, cached one.\n\n{table}{full_outputs}"
+    );
+    std::fs::write(path, report)
+        .unwrap_or_else(|e| fatal_error(&format!("Could not write {path}: {e}")));
+    println!("Wrote report to {path}");
+}
+
+fn table_cell(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+// The heading anchor GitHub generates: lowercase, punctuation dropped, spaces to dashes
+fn anchor(heading: &str) -> String {
+    heading
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-')
+        .map(|c| if c == ' ' { '-' } else { c.to_ascii_lowercase() })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use std::{
+    process::Command,
+    time::{Duration, Instant},
+};
+
+pub struct RunOutput {
+    pub lines: Vec<String>,
+    // Which part each line answers, for the lines the solver marked with one
+    pub line_parts: Vec<Option<usize>>,
+    pub stderr: String,
+    pub elapsed: Duration,
+    pub success: bool,
+}
+
+impl RunOutput {
+    // Splits the marks back off lines as the solver printed them
+    pub fn from_printed<'a>(
+        printed: impl Iterator<Item = &'a str>,
+        stderr: String,
+        elapsed: Duration,
+        success: bool,
+    ) -> RunOutput {
+        let (line_parts, lines) = printed.map(parse_line).unzip();
+        RunOutput { lines, line_parts, stderr, elapsed, success }
+    }
+
+    // The lines with their marks put back, as the solver printed them
+    pub fn printed_lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.lines.iter().zip(&self.line_parts).map(|(line, part)| match part {
+            Some(part) => label_line(*part, line),
+            None => line.clone(),
+        })
+    }
+
+    // Every line answering the part, or nothing if the solver didn't mark any
+    pub fn part_lines(&self, part: usize) -> Vec<&str> {
+        self.lines
+            .iter()
+            .zip(&self.line_parts)
+            .filter(|(_, &line_part)| line_part == Some(part))
+            .map(|(line, _)| line.as_str())
+            .collect()
+    }
+}
+
+// Set for the child process, so the solver marks which lines answer which part. A frontend can then
+// pick a part out of the output without relying on where its lines fall
+pub const LABEL_PARTS_VAR: &str = "AOC_LABEL_PARTS";
+
+// Goes either side of the part number, at the start of a line. It's a control character, so it
+// won't turn up in an answer
+const PART_MARKER: char = '\u{1e}';
+
+pub fn label_line(part: usize, line: &str) -> String {
+    format!("{PART_MARKER}{part}{PART_MARKER}{line}")
+}
+
+fn parse_line(line: &str) -> (Option<usize>, String) {
+    line.strip_prefix(PART_MARKER)
+        .and_then(|rest| rest.split_once(PART_MARKER))
+        .and_then(|(part, line)| Some((Some(part.parse().ok()?), line.to_string())))
+        .unwrap_or_else(|| (None, line.to_string()))
+}
+
+// Runs a day's solver in a child process of this same binary. This keeps a panicking solver (say,
+// on a hand-crafted edge case input) from taking down whatever is driving it, and lets us capture
+// exactly what the solver printed
+pub fn run_day(day_number: usize, extra_args: &[String]) -> std::io::Result<RunOutput> {
+    let exe = std::env::current_exe()?;
+
+    let start = Instant::now();
+    let output = Command::new(exe)
+        .arg(day_number.to_string())
+        .args(extra_args)
+        .env(LABEL_PARTS_VAR, "1")
+        .output()?;
+    let elapsed = start.elapsed();
+
+    // Skip the banner, callers only care about the answers
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(RunOutput::from_printed(
+        stdout.lines().filter(|line| !line.starts_with("--- Solving Day")),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+        elapsed,
+        output.status.success(),
+    ))
+}
@@ -0,0 +1,244 @@
+use std::{
+    fmt::Write as _,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use crate::{fatal_error, runner::run_day, DAYS};
+
+// Puzzle inputs are a few tens of KB at most, anything past this is a mistake
+const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+// A client that goes quiet partway through a request gives up its thread after this long
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+static REQUEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    body: Vec<u8>,
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+pub fn serve(port: u16) -> ! {
+    // Only ever listen on localhost, this isn't meant to be reachable from anywhere else
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .unwrap_or_else(|e| fatal_error(&format!("Could not listen on port {port}: {e}")));
+    println!("Listening on http://127.0.0.1:{port}");
+
+    for stream in listener.incoming() {
+        match stream {
+            // Slow days shouldn't hold up everyone else
+            Ok(stream) => {
+                thread::spawn(move || handle_connection(stream));
+            }
+            Err(e) => eprintln!("Could not accept connection: {e}"),
+        }
+    }
+
+    unreachable!("Listener stopped accepting connections");
+}
+
+fn handle_connection(stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let response = match read_request(&stream) {
+        Ok(request) => route(&request),
+        Err(message) => error_response("400 Bad Request", message),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        response.body.len()
+    );
+
+    // If the client has already gone away there's no one left to tell
+    let mut stream = &stream;
+    let _ =
+        stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(response.body.as_bytes()));
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, &'static str> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|_| "Could not read request")?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err("Malformed request line");
+    };
+
+    let mut content_length = 0;
+    let mut expects_continue = false;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|_| "Could not read headers")?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+
+        let (name, value) = line.split_once(':').ok_or("Malformed header")?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().map_err(|_| "Invalid Content-Length")?;
+        } else if name.eq_ignore_ascii_case("expect") {
+            expects_continue = value.eq_ignore_ascii_case("100-continue");
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err("Request body too large");
+    }
+
+    // curl (among others) holds back larger bodies until it's told to go ahead
+    if expects_continue {
+        let mut stream = stream;
+        stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").map_err(|_| "Could not write")?;
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|_| "Could not read request body")?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        body,
+    })
+}
+
+fn route(request: &Request) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/days") => Response { status: "200 OK", body: list_days() },
+        ("POST", path) if path.starts_with("/day/") => {
+            match path["/day/".len()..].parse::<usize>() {
+                Ok(n) if (1..=DAYS.len()).contains(&n) => solve_day(n, request),
+                _ => error_response("404 Not Found", "No such day"),
+            }
+        }
+        (_, "/days") => error_response("405 Method Not Allowed", "Use GET"),
+        (_, path) if path.starts_with("/day/") => {
+            error_response("405 Method Not Allowed", "Use POST")
+        }
+        _ => error_response("404 Not Found", "Not found"),
+    }
+}
+
+fn list_days() -> String {
+    let mut json = String::from("[");
+    for (i, day) in DAYS.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        write!(json, r#"{{"day":{},"title":{},"params":["#, i + 1, json_string(day.title)).unwrap();
+        for (j, param) in day.params.iter().enumerate() {
+            if j > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                r#"{{"name":{},"default":{},"description":{}}}"#,
+                json_string(param.name),
+                json_string(param.default),
+                json_string(param.description)
+            )
+            .unwrap();
+        }
+        json.push_str("]}");
+    }
+    json.push(']');
+    json
+}
+
+// The body is the puzzle input, and any query string parameters are passed on as day parameters,
+// e.g. `POST /day/15?row=10&search_max=20` for the example input
+fn solve_day(day_number: usize, request: &Request) -> Response {
+    let request_id = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let input_path =
+        std::env::temp_dir().join(format!("aoc-serve-{}-{request_id}.txt", std::process::id()));
+    if std::fs::write(&input_path, &request.body).is_err() {
+        return error_response("500 Internal Server Error", "Could not store input");
+    }
+
+    let mut args = vec!["--input".to_string(), input_path.to_string_lossy().into_owned()];
+    for pair in request.query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (Some(name), Some(value)) = (percent_decode(name), percent_decode(value)) else {
+            let _ = std::fs::remove_file(&input_path);
+            return error_response("400 Bad Request", "Malformed query string");
+        };
+        args.push("--param".to_string());
+        args.push(format!("{name}={value}"));
+    }
+
+    let result = run_day(day_number, &args);
+    let _ = std::fs::remove_file(&input_path);
+
+    let Ok(output) = result else {
+        return error_response("500 Internal Server Error", "Could not run solver");
+    };
+
+    let answers = output.lines.iter().map(|line| json_string(line)).collect::<Vec<_>>().join(",");
+    let error = if output.success { "null".to_string() } else { json_string(output.stderr.trim()) };
+    let body = format!(
+        r#"{{"day":{day_number},"title":{},"success":{},"answers":[{answers}],"elapsed_ms":{:.3},"error":{error}}}"#,
+        json_string(DAYS[day_number - 1].title),
+        output.success,
+        output.elapsed.as_secs_f64() * 1000.0,
+    );
+
+    // A solver falling over is most likely down to the input it was given
+    let status = if output.success { "200 OK" } else { "422 Unprocessable Entity" };
+    Response { status, body }
+}
+
+// Undoes the encoding clients use for query strings: %XX for a byte, and + for a space
+fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        bytes.push(match b {
+            b'+' => b' ',
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?
+            }
+            b => b,
+        });
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn error_response(status: &'static str, message: &str) -> Response {
+    Response { status, body: format!(r#"{{"error":{}}}"#, json_string(message)) }
+}
+
+fn json_string(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,93 @@
+use std::{fs, path::PathBuf, thread, time::Duration};
+
+use crate::{
+    fatal_error,
+    helpers::input_path,
+    runner::{run_day, RunOutput},
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// Any options are passed along to the solver. If they point it at a different input, that's the
+// file we watch instead. Like the solver, the last --input wins
+pub fn watch(day_number: usize, options: &[String]) -> ! {
+    let input = options.chunks(2).rev().find_map(|pair| match pair {
+        [option, value] if option == "--input" => Some(value),
+        _ => None,
+    });
+    let path = match input {
+        Some(value) if value == "-" => {
+            fatal_error("Stdin can't be watched, the input has to be a file")
+        }
+        Some(value) => PathBuf::from(value),
+        None => input_path(day_number),
+    };
+    println!("Watching {} for changes, press Ctrl-C to stop", path.display());
+
+    let mut last_modified = None;
+    let mut previous: Option<RunOutput> = None;
+    let mut run_count = 0;
+
+    loop {
+        // A missing file (e.g. mid-save in some editors) counts as a change too, but there's
+        // nothing to run until it comes back
+        let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+        if modified != last_modified {
+            last_modified = modified;
+
+            if modified.is_none() {
+                println!("Input file is missing, waiting for it to reappear");
+            } else {
+                match run_day(day_number, options) {
+                    Ok(output) => {
+                        run_count += 1;
+                        print_run(run_count, &output, previous.as_ref());
+                        previous = Some(output);
+                    }
+                    Err(e) => eprintln!("Could not run solver: {e}"),
+                }
+            }
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn print_run(run_count: usize, output: &RunOutput, previous: Option<&RunOutput>) {
+    match previous {
+        Some(prev) => println!(
+            "--- Run {run_count} took {:.2?} (previously {:.2?}) ---",
+            output.elapsed, prev.elapsed
+        ),
+        None => println!("--- Run {run_count} took {:.2?} ---", output.elapsed),
+    }
+
+    // Answers come out one per line in a fixed order, so a line-by-line comparison is all the diff
+    // we need. Unchanged lines are indented to keep them aligned with the changed ones
+    let Some(prev) = previous else {
+        output.lines.iter().for_each(|line| println!("  {line}"));
+        return print_failure(output);
+    };
+    for i in 0..output.lines.len().max(prev.lines.len()) {
+        match (prev.lines.get(i), output.lines.get(i)) {
+            (Some(old), Some(new)) if old == new => println!("  {new}"),
+            (old, new) => {
+                if let Some(old) = old {
+                    println!("- {old}");
+                }
+                if let Some(new) = new {
+                    println!("+ {new}");
+                }
+            }
+        }
+    }
+
+    print_failure(output);
+}
+
+fn print_failure(output: &RunOutput) {
+    if !output.success {
+        println!("Solver failed:");
+        print!("{}", output.stderr);
+    }
+}